@@ -0,0 +1,17 @@
+use std::{ env, fs, path::PathBuf };
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    if env::var_os("CARGO_FEATURE_RT").is_none() {
+        return;
+    }
+
+    // Make link.x visible to the linker of the final binary, memory.x comes
+    // from the application
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out.join("link.x"), include_bytes!("link.x")).unwrap();
+
+    println!("cargo:rustc-link-search={}", out.display());
+    println!("cargo:rerun-if-changed=link.x");
+}
@@ -0,0 +1,9 @@
+/* Example memory.x for a STM32F427/429 with 2 MiB flash. The application
+   ships its own next to its Cargo.toml, with FLASH LENGTH matching the part. */
+MEMORY
+{
+    FLASH  : ORIGIN = 0x08000000, LENGTH = 2048K
+    /* SRAM1, SRAM2 and SRAM3 are contiguous */
+    RAM    : ORIGIN = 0x20000000, LENGTH = 192K
+    CCMRAM : ORIGIN = 0x10000000, LENGTH = 64K
+}
@@ -0,0 +1,172 @@
+/* Linker script for the `rt` feature, link with `-C link-arg=-Tlink.x` */
+
+INCLUDE memory.x
+
+ENTRY(__reset_handler);
+
+EXTERN(__RESET_VECTOR);
+EXTERN(__EXCEPTIONS);
+EXTERN(__INTERRUPTS);
+
+/* Full-descending main stack at the top of SRAM */
+PROVIDE(_stack_start = ORIGIN(RAM) + LENGTH(RAM));
+
+PROVIDE(DefaultHandler = __default_handler);
+
+PROVIDE(__nmi_handler = DefaultHandler);
+PROVIDE(__hard_fault_handler = DefaultHandler);
+PROVIDE(__mem_manage_handler = DefaultHandler);
+PROVIDE(__bus_fault_handler = DefaultHandler);
+PROVIDE(__usage_fault_handler = DefaultHandler);
+PROVIDE(__svcall_handler = DefaultHandler);
+PROVIDE(__debug_monitor_handler = DefaultHandler);
+PROVIDE(__pendsv_handler = DefaultHandler);
+PROVIDE(__systick_handler = DefaultHandler);
+
+PROVIDE(__irq0_handler = DefaultHandler);
+PROVIDE(__irq1_handler = DefaultHandler);
+PROVIDE(__irq2_handler = DefaultHandler);
+PROVIDE(__irq3_handler = DefaultHandler);
+PROVIDE(__irq4_handler = DefaultHandler);
+PROVIDE(__irq5_handler = DefaultHandler);
+PROVIDE(__irq6_handler = DefaultHandler);
+PROVIDE(__irq7_handler = DefaultHandler);
+PROVIDE(__irq8_handler = DefaultHandler);
+PROVIDE(__irq9_handler = DefaultHandler);
+PROVIDE(__irq10_handler = DefaultHandler);
+PROVIDE(__irq11_handler = DefaultHandler);
+PROVIDE(__irq12_handler = DefaultHandler);
+PROVIDE(__irq13_handler = DefaultHandler);
+PROVIDE(__irq14_handler = DefaultHandler);
+PROVIDE(__irq15_handler = DefaultHandler);
+PROVIDE(__irq16_handler = DefaultHandler);
+PROVIDE(__irq17_handler = DefaultHandler);
+PROVIDE(__irq18_handler = DefaultHandler);
+PROVIDE(__irq19_handler = DefaultHandler);
+PROVIDE(__irq20_handler = DefaultHandler);
+PROVIDE(__irq21_handler = DefaultHandler);
+PROVIDE(__irq22_handler = DefaultHandler);
+PROVIDE(__irq23_handler = DefaultHandler);
+PROVIDE(__irq24_handler = DefaultHandler);
+PROVIDE(__irq25_handler = DefaultHandler);
+PROVIDE(__irq26_handler = DefaultHandler);
+PROVIDE(__irq27_handler = DefaultHandler);
+PROVIDE(__irq28_handler = DefaultHandler);
+PROVIDE(__irq29_handler = DefaultHandler);
+PROVIDE(__irq30_handler = DefaultHandler);
+PROVIDE(__irq31_handler = DefaultHandler);
+PROVIDE(__irq32_handler = DefaultHandler);
+PROVIDE(__irq33_handler = DefaultHandler);
+PROVIDE(__irq34_handler = DefaultHandler);
+PROVIDE(__irq35_handler = DefaultHandler);
+PROVIDE(__irq36_handler = DefaultHandler);
+PROVIDE(__irq37_handler = DefaultHandler);
+PROVIDE(__irq38_handler = DefaultHandler);
+PROVIDE(__irq39_handler = DefaultHandler);
+PROVIDE(__irq40_handler = DefaultHandler);
+PROVIDE(__irq41_handler = DefaultHandler);
+PROVIDE(__irq42_handler = DefaultHandler);
+PROVIDE(__irq43_handler = DefaultHandler);
+PROVIDE(__irq44_handler = DefaultHandler);
+PROVIDE(__irq45_handler = DefaultHandler);
+PROVIDE(__irq46_handler = DefaultHandler);
+PROVIDE(__irq47_handler = DefaultHandler);
+PROVIDE(__irq48_handler = DefaultHandler);
+PROVIDE(__irq49_handler = DefaultHandler);
+PROVIDE(__irq50_handler = DefaultHandler);
+PROVIDE(__irq51_handler = DefaultHandler);
+PROVIDE(__irq52_handler = DefaultHandler);
+PROVIDE(__irq53_handler = DefaultHandler);
+PROVIDE(__irq54_handler = DefaultHandler);
+PROVIDE(__irq55_handler = DefaultHandler);
+PROVIDE(__irq56_handler = DefaultHandler);
+PROVIDE(__irq57_handler = DefaultHandler);
+PROVIDE(__irq58_handler = DefaultHandler);
+PROVIDE(__irq59_handler = DefaultHandler);
+PROVIDE(__irq60_handler = DefaultHandler);
+PROVIDE(__irq61_handler = DefaultHandler);
+PROVIDE(__irq62_handler = DefaultHandler);
+PROVIDE(__irq63_handler = DefaultHandler);
+PROVIDE(__irq64_handler = DefaultHandler);
+PROVIDE(__irq65_handler = DefaultHandler);
+PROVIDE(__irq66_handler = DefaultHandler);
+PROVIDE(__irq67_handler = DefaultHandler);
+PROVIDE(__irq68_handler = DefaultHandler);
+PROVIDE(__irq69_handler = DefaultHandler);
+PROVIDE(__irq70_handler = DefaultHandler);
+PROVIDE(__irq71_handler = DefaultHandler);
+PROVIDE(__irq72_handler = DefaultHandler);
+PROVIDE(__irq73_handler = DefaultHandler);
+PROVIDE(__irq74_handler = DefaultHandler);
+PROVIDE(__irq75_handler = DefaultHandler);
+PROVIDE(__irq76_handler = DefaultHandler);
+PROVIDE(__irq77_handler = DefaultHandler);
+PROVIDE(__irq78_handler = DefaultHandler);
+PROVIDE(__irq79_handler = DefaultHandler);
+PROVIDE(__irq80_handler = DefaultHandler);
+PROVIDE(__irq81_handler = DefaultHandler);
+PROVIDE(__irq82_handler = DefaultHandler);
+PROVIDE(__irq83_handler = DefaultHandler);
+PROVIDE(__irq84_handler = DefaultHandler);
+PROVIDE(__irq85_handler = DefaultHandler);
+PROVIDE(__irq86_handler = DefaultHandler);
+PROVIDE(__irq87_handler = DefaultHandler);
+PROVIDE(__irq88_handler = DefaultHandler);
+PROVIDE(__irq89_handler = DefaultHandler);
+PROVIDE(__irq90_handler = DefaultHandler);
+
+SECTIONS
+{
+    .vector_table ORIGIN(FLASH) :
+    {
+        LONG(_stack_start);
+        KEEP(*(.vector_table.reset_vector));
+        KEEP(*(.vector_table.exceptions));
+        KEEP(*(.vector_table.interrupts));
+    } > FLASH
+
+    .text :
+    {
+        *(.text .text.*);
+    } > FLASH
+
+    .rodata : ALIGN(4)
+    {
+        *(.rodata .rodata.*);
+        . = ALIGN(4);
+    } > FLASH
+
+    .data : ALIGN(4)
+    {
+        __sdata = .;
+        *(.data .data.*);
+        . = ALIGN(4);
+        __edata = .;
+    } > RAM AT > FLASH
+
+    __sidata = LOADADDR(.data);
+
+    .bss (NOLOAD) : ALIGN(4)
+    {
+        __sbss = .;
+        *(.bss .bss.*);
+        *(COMMON);
+        . = ALIGN(4);
+        __ebss = .;
+    } > RAM
+
+    /* Not initialised by the reset handler, only reachable by the CPU */
+    .ccmram (NOLOAD) : ALIGN(4)
+    {
+        *(.ccmram .ccmram.*);
+        . = ALIGN(4);
+    } > CCMRAM
+
+    /DISCARD/ :
+    {
+        *(.ARM.exidx .ARM.exidx.*);
+    }
+}
+
+ASSERT(ORIGIN(FLASH) % 512 == 0, "vector table must be aligned to 512 bytes");
+ASSERT(__ebss <= _stack_start, "no room left for the stack in RAM");
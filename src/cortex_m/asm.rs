@@ -0,0 +1,79 @@
+//! Thin wrappers around Cortex-M instructions without a Rust equivalent.
+//!
+//! On other architectures (e.g. when running host tests) barriers are reduced
+//! to compiler fences and the remaining instructions are no-ops.
+
+#[cfg(not(target_arch = "arm"))]
+use core::sync::atomic::{ compiler_fence, Ordering };
+
+/// Data Synchronization Barrier
+#[inline(always)]
+pub fn dsb() {
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("dsb sy", options(nostack, preserves_flags));
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Data Memory Barrier
+#[inline(always)]
+pub fn dmb() {
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("dmb sy", options(nostack, preserves_flags));
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Instruction Synchronization Barrier
+#[inline(always)]
+pub fn isb() {
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("isb sy", options(nostack, preserves_flags));
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// No Operation
+#[inline(always)]
+pub fn nop() {
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("nop", options(nomem, nostack, preserves_flags));
+    }
+}
+
+/// Wait For Interrupt
+#[inline(always)]
+pub fn wfi() {
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("wfi", options(nomem, nostack, preserves_flags));
+    }
+}
+
+/// Wait For Event
+#[inline(always)]
+pub fn wfe() {
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("wfe", options(nomem, nostack, preserves_flags));
+    }
+}
+
+/// Send Event
+#[inline(always)]
+pub fn sev() {
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("sev", options(nomem, nostack, preserves_flags));
+    }
+}
@@ -0,0 +1,54 @@
+#![allow(dead_code)]
+
+use self::register::*;
+
+mod register;
+
+pub struct RegisterBlock {
+    /// Debug Halting Control and Status Register
+    dhcsr: DebugHaltingControlAndStatusRegister,
+
+    /// Debug Core Register Selector Register
+    __reserved0: u32,
+
+    /// Debug Core Register Data Register
+    __reserved1: u32,
+
+    /// Debug Exception and Monitor Control Register
+    demcr: DebugExceptionAndMonitorControlRegister,
+}
+
+impl RegisterBlock {
+    /// Enables the DWT, ITM, ETM and TPIU blocks
+    #[inline]
+    pub fn enable_trace(&mut self) {
+        self.demcr.enable_trace()
+    }
+
+    #[inline]
+    pub fn disable_trace(&mut self) {
+        self.demcr.disable_trace()
+    }
+
+    #[inline]
+    pub fn trace_is_enabled(&self) -> bool {
+        self.demcr.trace_is_enabled()
+    }
+
+    /// Enables the DebugMonitor exception, used by DWT watchpoints when no
+    /// debugger is halting the core
+    #[inline]
+    pub fn enable_debug_monitor(&mut self) {
+        self.demcr.enable_monitor()
+    }
+
+    #[inline]
+    pub fn disable_debug_monitor(&mut self) {
+        self.demcr.disable_monitor()
+    }
+
+    #[inline]
+    pub fn debugger_is_connected(&self) -> bool {
+        self.dhcsr.debugger_is_connected()
+    }
+}
@@ -0,0 +1,69 @@
+use register::register;
+
+#[register(u32)]
+pub(super) struct DebugHaltingControlAndStatusRegister {
+    #[bits(1, r, get = debugger_is_connected)]
+    pub(super) C_DEBUGEN: bool,
+
+    #[bits(31)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct DebugExceptionAndMonitorControlRegister {
+    #[bits(1, rw, get = core_reset_catch_is_enabled, set = set_core_reset_catch)]
+    pub(super) VC_CORERESET: bool,
+
+    #[bits(3)]
+    __: u32,
+
+    #[bits(
+        1,
+        rw,
+        get = memory_management_fault_catch_is_enabled,
+        set = set_memory_management_fault_catch
+    )]
+    pub(super) VC_MMERR: bool,
+
+    #[bits(1, rw, get = no_coprocessor_catch_is_enabled, set = set_no_coprocessor_catch)]
+    pub(super) VC_NOCPERR: bool,
+
+    #[bits(1, rw, get = checking_error_catch_is_enabled, set = set_checking_error_catch)]
+    pub(super) VC_CHKERR: bool,
+
+    #[bits(1, rw, get = state_error_catch_is_enabled, set = set_state_error_catch)]
+    pub(super) VC_STATERR: bool,
+
+    #[bits(1, rw, get = bus_fault_catch_is_enabled, set = set_bus_fault_catch)]
+    pub(super) VC_BUSERR: bool,
+
+    #[bits(1, rw, get = interrupt_error_catch_is_enabled, set = set_interrupt_error_catch)]
+    pub(super) VC_INTERR: bool,
+
+    #[bits(1, rw, get = hard_fault_catch_is_enabled, set = set_hard_fault_catch)]
+    pub(super) VC_HARDERR: bool,
+
+    #[bits(5)]
+    __: u32,
+
+    #[bits(1, rwc, get = monitor_is_enabled, set = enable_monitor, clear = disable_monitor)]
+    pub(super) MON_EN: bool,
+
+    #[bits(1, rw, get = monitor_is_pending, set = set_monitor_pending)]
+    pub(super) MON_PEND: bool,
+
+    #[bits(1, rw, get = monitor_step_is_enabled, set = set_monitor_step)]
+    pub(super) MON_STEP: bool,
+
+    #[bits(1, rw, get = monitor_request_is_set, set = set_monitor_request)]
+    pub(super) MON_REQ: bool,
+
+    #[bits(4)]
+    __: u32,
+
+    #[bits(1, rwc, get = trace_is_enabled, set = enable_trace, clear = disable_trace)]
+    pub(super) TRCENA: bool,
+
+    #[bits(7)]
+    __: u32,
+}
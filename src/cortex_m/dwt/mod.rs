@@ -0,0 +1,177 @@
+#![allow(dead_code)]
+
+use core::fmt;
+
+use super::Peripherals;
+
+use self::register::*;
+
+mod register;
+
+pub struct RegisterBlock {
+    /// Control Register
+    ctrl: ControlRegister,
+
+    /// Cycle Count Register
+    cyccnt: CycleCountRegister,
+
+    /// CPI Count Register
+    __reserved0: u32,
+
+    /// Exception Overhead Count Register
+    __reserved1: u32,
+
+    /// Sleep Count Register
+    __reserved2: u32,
+
+    /// LSU Count Register
+    __reserved3: u32,
+
+    /// Folded-instruction Count Register
+    __reserved4: u32,
+
+    /// Program Counter Sample Register
+    __reserved5: u32,
+
+    /// Comparators
+    comp: [Comparator; 4],
+}
+
+pub struct Comparator {
+    /// Comparator Register
+    comp: ComparatorRegister,
+
+    /// Comparator Mask Register
+    mask: ComparatorMaskRegister,
+
+    /// Comparator Function Register
+    function: ComparatorFunctionRegister,
+
+    __reserved: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Comparator number is not implemented
+    InvalidComparator(usize),
+
+    /// Watched area is not a power of two or larger than 32 KiB
+    InvalidSize(u32),
+
+    /// Address is not aligned to the watched area size
+    UnalignedAddress(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidComparator(n) => f.write_fmt(format_args!("Invalid comparator: {}", n)),
+            Error::InvalidSize(s) => f.write_fmt(format_args!("Invalid watch size: {}", s)),
+            Error::UnalignedAddress(a) =>
+                f.write_fmt(format_args!("Unaligned address: {:#010x}", a)),
+        }
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Data accesses that trigger a watchpoint debug event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchpointAccess {
+    Read = 0b0101,
+    Write = 0b0110,
+    ReadWrite = 0b0111,
+}
+
+impl RegisterBlock {
+    /// Largest area a single comparator can watch
+    pub const MAX_WATCH_SIZE: u32 = 1 << 15;
+
+    /// Starts the cycle counter, enabling trace in DEMCR first
+    pub fn enable_cycle_counter(&mut self) {
+        Peripherals::dcb().enable_trace();
+
+        self.ctrl.enable_cycle_counter();
+    }
+
+    #[inline]
+    pub fn disable_cycle_counter(&mut self) {
+        self.ctrl.disable_cycle_counter()
+    }
+
+    #[inline]
+    pub fn cycle_counter_is_enabled(&self) -> bool {
+        self.ctrl.cycle_counter_is_enabled()
+    }
+
+    /// Core clock cycles counted so far, wraps on overflow
+    #[inline]
+    pub fn cycle_count(&self) -> u32 {
+        self.cyccnt.get_count()
+    }
+
+    #[inline]
+    pub fn reset_cycle_count(&mut self) {
+        self.cyccnt.set_count(0)
+    }
+
+    /// Number of implemented comparators
+    #[inline]
+    pub fn comparators(&self) -> usize {
+        self.ctrl.get_comparator_count() as usize
+    }
+
+    /// Configures comparator `n` to raise a debug event when `size` bytes at
+    /// `addr` are accessed. `size` has to be a power of two and `addr` aligned
+    /// to it.
+    pub fn set_watchpoint(
+        &mut self,
+        n: usize,
+        addr: u32,
+        size: u32,
+        access: WatchpointAccess
+    ) -> Result<()> {
+        self.check_comparator(n)?;
+
+        if !size.is_power_of_two() || size > Self::MAX_WATCH_SIZE {
+            return Err(Error::InvalidSize(size));
+        }
+
+        if addr & (size - 1) != 0 {
+            return Err(Error::UnalignedAddress(addr));
+        }
+
+        Peripherals::dcb().enable_trace();
+
+        let comp = &mut self.comp[n];
+        comp.function.set_function(0);
+        comp.comp.set_value(addr);
+        comp.mask.set_mask(size.trailing_zeros() as u8);
+        comp.function.set_function(access as u8);
+
+        Ok(())
+    }
+
+    pub fn clear_watchpoint(&mut self, n: usize) -> Result<()> {
+        self.check_comparator(n)?;
+
+        self.comp[n].function.set_function(0);
+
+        Ok(())
+    }
+
+    /// Returns `true` if comparator `n` matched since the last call
+    pub fn watchpoint_is_matched(&self, n: usize) -> Result<bool> {
+        self.check_comparator(n)?;
+
+        Ok(self.comp[n].function.is_matched())
+    }
+
+    fn check_comparator(&self, n: usize) -> Result<()> {
+        if n >= self.comparators().min(self.comp.len()) {
+            return Err(Error::InvalidComparator(n));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use register::register;
+
+#[register(u32)]
+pub(super) struct ControlRegister {
+    #[bits(
+        1,
+        rwc,
+        get = cycle_counter_is_enabled,
+        set = enable_cycle_counter,
+        clear = disable_cycle_counter
+    )]
+    pub(super) CYCCNTENA: bool,
+
+    #[bits(4, rw, get = get_post_counter_preset, set = set_post_counter_preset)]
+    pub(super) POSTPRESET: u8,
+
+    #[bits(4, rw, get = get_post_counter_init, set = set_post_counter_init)]
+    pub(super) POSTINIT: u8,
+
+    #[bits(1, rw, get = get_cycle_tap, set = set_cycle_tap)]
+    pub(super) CYCTAP: bool,
+
+    #[bits(2, rw, get = get_sync_tap, set = set_sync_tap)]
+    pub(super) SYNCTAP: u8,
+
+    #[bits(
+        1,
+        rwc,
+        get = pc_sampling_is_enabled,
+        set = enable_pc_sampling,
+        clear = disable_pc_sampling
+    )]
+    pub(super) PCSAMPLENA: bool,
+
+    #[bits(3)]
+    __: u32,
+
+    #[bits(
+        1,
+        rwc,
+        get = exception_trace_is_enabled,
+        set = enable_exception_trace,
+        clear = disable_exception_trace
+    )]
+    pub(super) EXCTRCENA: bool,
+
+    #[bits(1, rw, get = cpi_counter_event_is_enabled, set = set_cpi_counter_event)]
+    pub(super) CPIEVTENA: bool,
+
+    #[bits(1, rw, get = exception_counter_event_is_enabled, set = set_exception_counter_event)]
+    pub(super) EXCEVTENA: bool,
+
+    #[bits(1, rw, get = sleep_counter_event_is_enabled, set = set_sleep_counter_event)]
+    pub(super) SLEEPEVTENA: bool,
+
+    #[bits(1, rw, get = lsu_counter_event_is_enabled, set = set_lsu_counter_event)]
+    pub(super) LSUEVTENA: bool,
+
+    #[bits(
+        1,
+        rw,
+        get = folded_instruction_counter_event_is_enabled,
+        set = set_folded_instruction_counter_event
+    )]
+    pub(super) FOLDEVTENA: bool,
+
+    #[bits(1, rw, get = cycle_counter_event_is_enabled, set = set_cycle_counter_event)]
+    pub(super) CYCEVTENA: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, r, get = has_no_profile_counters)]
+    pub(super) NOPRFCNT: bool,
+
+    #[bits(1, r, get = has_no_cycle_counter)]
+    pub(super) NOCYCCNT: bool,
+
+    #[bits(1, r, get = has_no_external_trigger)]
+    pub(super) NOEXTTRIG: bool,
+
+    #[bits(1, r, get = has_no_trace_packets)]
+    pub(super) NOTRCPKT: bool,
+
+    #[bits(4, r, get = get_comparator_count)]
+    pub(super) NUMCOMP: u8,
+}
+
+#[register(u32)]
+pub(super) struct CycleCountRegister {
+    #[bits(32, rw, get = get_count, set = set_count)]
+    pub(super) CYCCNT: u32,
+}
+
+#[register(u32)]
+pub(super) struct ComparatorRegister {
+    #[bits(32, rw, get = get_value, set = set_value)]
+    pub(super) COMP: u32,
+}
+
+#[register(u32)]
+pub(super) struct ComparatorMaskRegister {
+    #[bits(5, rw, get = get_mask, set = set_mask)]
+    pub(super) MASK: u8,
+
+    #[bits(27)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct ComparatorFunctionRegister {
+    #[bits(4, rw, get = get_function, set = set_function)]
+    pub(super) FUNCTION: u8,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, rw, get = emit_range_is_enabled, set = set_emit_range)]
+    pub(super) EMITRANGE: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, rw, get = cycle_match_is_enabled, set = set_cycle_match)]
+    pub(super) CYCMATCH: bool,
+
+    #[bits(1, rw, get = data_value_match_is_enabled, set = set_data_value_match)]
+    pub(super) DATAVMATCH: bool,
+
+    #[bits(1, r, get = supports_linked_comparator)]
+    pub(super) LNK1ENA: bool,
+
+    #[bits(2, rw, get = get_data_value_size, set = set_data_value_size)]
+    pub(super) DATAVSIZE: u8,
+
+    #[bits(4, rw, get = get_data_value_address0, set = set_data_value_address0)]
+    pub(super) DATAVADDR0: u8,
+
+    #[bits(4, rw, get = get_data_value_address1, set = set_data_value_address1)]
+    pub(super) DATAVADDR1: u8,
+
+    #[bits(4)]
+    __: u32,
+
+    #[bits(1, r, get = is_matched)]
+    pub(super) MATCHED: bool,
+
+    #[bits(7)]
+    __: u32,
+}
@@ -0,0 +1,377 @@
+//! Decoding of the fault status registers and the stacked exception frame.
+//!
+//! The `decode_*` functions only operate on raw register values so they can be
+//! exercised on the host. [`FaultReport::capture`] reads the live registers.
+
+use core::fmt;
+
+use super::scb::RegisterBlock as SCB;
+
+/// Registers pushed onto the active stack on exception entry
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExceptionFrame {
+    pub r0: u32,
+    pub r1: u32,
+    pub r2: u32,
+    pub r3: u32,
+    pub r12: u32,
+    pub lr: u32,
+    pub pc: u32,
+    pub xpsr: u32,
+}
+
+/// MemManage Fault Status (CFSR[7:0])
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemManageFault {
+    pub instruction_access_violation: bool,
+    pub data_access_violation: bool,
+    pub unstacking: bool,
+    pub stacking: bool,
+    pub lazy_fp_preservation: bool,
+
+    /// MMFAR, only present if MMARVALID is set
+    pub address: Option<u32>,
+}
+
+/// BusFault Status (CFSR[15:8])
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BusFault {
+    pub instruction_bus_error: bool,
+    pub precise_data_bus_error: bool,
+    pub imprecise_data_bus_error: bool,
+    pub unstacking: bool,
+    pub stacking: bool,
+    pub lazy_fp_preservation: bool,
+
+    /// BFAR, only present if BFARVALID is set
+    pub address: Option<u32>,
+}
+
+/// UsageFault Status (CFSR[31:16])
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UsageFault {
+    pub undefined_instruction: bool,
+    pub invalid_state: bool,
+    pub invalid_pc_load: bool,
+    pub no_coprocessor: bool,
+    pub unaligned_access: bool,
+    pub divide_by_zero: bool,
+}
+
+/// HardFault Status
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HardFault {
+    pub vector_table_read: bool,
+    pub forced: bool,
+    pub debug_event: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FaultReport {
+    pub hard_fault: HardFault,
+    pub mem_manage: MemManageFault,
+    pub bus: BusFault,
+    pub usage: UsageFault,
+    pub frame: Option<ExceptionFrame>,
+}
+
+#[inline]
+fn bit(val: u32, pos: u32) -> bool {
+    (val & (1 << pos)) != 0
+}
+
+pub fn decode_mem_manage_fault(cfsr: u32, mmfar: u32) -> MemManageFault {
+    MemManageFault {
+        instruction_access_violation: bit(cfsr, 0),
+        data_access_violation: bit(cfsr, 1),
+        unstacking: bit(cfsr, 3),
+        stacking: bit(cfsr, 4),
+        lazy_fp_preservation: bit(cfsr, 5),
+        address: if bit(cfsr, 7) { Some(mmfar) } else { None },
+    }
+}
+
+pub fn decode_bus_fault(cfsr: u32, bfar: u32) -> BusFault {
+    BusFault {
+        instruction_bus_error: bit(cfsr, 8),
+        precise_data_bus_error: bit(cfsr, 9),
+        imprecise_data_bus_error: bit(cfsr, 10),
+        unstacking: bit(cfsr, 11),
+        stacking: bit(cfsr, 12),
+        lazy_fp_preservation: bit(cfsr, 13),
+        address: if bit(cfsr, 15) { Some(bfar) } else { None },
+    }
+}
+
+pub fn decode_usage_fault(cfsr: u32) -> UsageFault {
+    UsageFault {
+        undefined_instruction: bit(cfsr, 16),
+        invalid_state: bit(cfsr, 17),
+        invalid_pc_load: bit(cfsr, 18),
+        no_coprocessor: bit(cfsr, 19),
+        unaligned_access: bit(cfsr, 24),
+        divide_by_zero: bit(cfsr, 25),
+    }
+}
+
+pub fn decode_hard_fault(hfsr: u32) -> HardFault {
+    HardFault {
+        vector_table_read: bit(hfsr, 1),
+        forced: bit(hfsr, 30),
+        debug_event: bit(hfsr, 31),
+    }
+}
+
+impl MemManageFault {
+    pub fn is_active(&self) -> bool {
+        self.instruction_access_violation ||
+            self.data_access_violation ||
+            self.unstacking ||
+            self.stacking ||
+            self.lazy_fp_preservation
+    }
+}
+
+impl BusFault {
+    pub fn is_active(&self) -> bool {
+        self.instruction_bus_error ||
+            self.precise_data_bus_error ||
+            self.imprecise_data_bus_error ||
+            self.unstacking ||
+            self.stacking ||
+            self.lazy_fp_preservation
+    }
+}
+
+impl UsageFault {
+    pub fn is_active(&self) -> bool {
+        self.undefined_instruction ||
+            self.invalid_state ||
+            self.invalid_pc_load ||
+            self.no_coprocessor ||
+            self.unaligned_access ||
+            self.divide_by_zero
+    }
+}
+
+impl HardFault {
+    pub fn is_active(&self) -> bool {
+        self.vector_table_read || self.forced || self.debug_event
+    }
+}
+
+impl FaultReport {
+    pub fn decode(cfsr: u32, hfsr: u32, mmfar: u32, bfar: u32) -> Self {
+        Self {
+            hard_fault: decode_hard_fault(hfsr),
+            mem_manage: decode_mem_manage_fault(cfsr, mmfar),
+            bus: decode_bus_fault(cfsr, bfar),
+            usage: decode_usage_fault(cfsr),
+            frame: None,
+        }
+    }
+
+    /// Reads the fault status registers and attaches the stacked `frame`
+    pub fn capture(scb: &SCB, frame: Option<&ExceptionFrame>) -> Self {
+        let mut report = Self::decode(
+            scb.fault_status(),
+            scb.hard_fault_status(),
+            scb.memory_management_fault_address(),
+            scb.bus_fault_address()
+        );
+        report.frame = frame.copied();
+
+        report
+    }
+}
+
+impl fmt::Display for FaultReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hf = &self.hard_fault;
+        if hf.is_active() {
+            f.write_str("HardFault:")?;
+            flag(f, hf.vector_table_read, "VECTTBL")?;
+            flag(f, hf.forced, "FORCED")?;
+            flag(f, hf.debug_event, "DEBUGEVT")?;
+            f.write_str("\r\n")?;
+        }
+
+        let mm = &self.mem_manage;
+        if mm.is_active() {
+            f.write_str("MemManage:")?;
+            flag(f, mm.instruction_access_violation, "IACCVIOL")?;
+            flag(f, mm.data_access_violation, "DACCVIOL")?;
+            flag(f, mm.unstacking, "MUNSTKERR")?;
+            flag(f, mm.stacking, "MSTKERR")?;
+            flag(f, mm.lazy_fp_preservation, "MLSPERR")?;
+            if let Some(addr) = mm.address {
+                f.write_fmt(format_args!(" at {:#010x}", addr))?;
+            }
+            f.write_str("\r\n")?;
+        }
+
+        let bf = &self.bus;
+        if bf.is_active() {
+            f.write_str("BusFault:")?;
+            flag(f, bf.instruction_bus_error, "IBUSERR")?;
+            flag(f, bf.precise_data_bus_error, "PRECISERR")?;
+            flag(f, bf.imprecise_data_bus_error, "IMPRECISERR")?;
+            flag(f, bf.unstacking, "UNSTKERR")?;
+            flag(f, bf.stacking, "STKERR")?;
+            flag(f, bf.lazy_fp_preservation, "LSPERR")?;
+            if let Some(addr) = bf.address {
+                f.write_fmt(format_args!(" at {:#010x}", addr))?;
+            }
+            f.write_str("\r\n")?;
+        }
+
+        let uf = &self.usage;
+        if uf.is_active() {
+            f.write_str("UsageFault:")?;
+            flag(f, uf.undefined_instruction, "UNDEFINSTR")?;
+            flag(f, uf.invalid_state, "INVSTATE")?;
+            flag(f, uf.invalid_pc_load, "INVPC")?;
+            flag(f, uf.no_coprocessor, "NOCP")?;
+            flag(f, uf.unaligned_access, "UNALIGNED")?;
+            flag(f, uf.divide_by_zero, "DIVBYZERO")?;
+            f.write_str("\r\n")?;
+        }
+
+        if let Some(frame) = &self.frame {
+            f.write_fmt(
+                format_args!(
+                    "R0={:#010x} R1={:#010x} R2={:#010x} R3={:#010x}\r\n",
+                    frame.r0,
+                    frame.r1,
+                    frame.r2,
+                    frame.r3
+                )
+            )?;
+            f.write_fmt(
+                format_args!(
+                    "R12={:#010x} LR={:#010x} PC={:#010x} xPSR={:#010x}\r\n",
+                    frame.r12,
+                    frame.lr,
+                    frame.pc,
+                    frame.xpsr
+                )
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[inline]
+fn flag(f: &mut fmt::Formatter<'_>, set: bool, name: &str) -> fmt::Result {
+    if set { f.write_fmt(format_args!(" {}", name)) } else { Ok(()) }
+}
+
+static mut HARD_FAULT_HOOK: Option<fn(&FaultReport)> = None;
+
+/// Installs the function called with the decoded report when a HardFault occurs.
+///
+/// The core is parked in an endless loop after the hook returns. Only the
+/// HardFault handler of the `rt` feature calls it.
+pub fn set_hard_fault_hook(hook: fn(&FaultReport)) {
+    unsafe {
+        HARD_FAULT_HOOK = Some(hook);
+    }
+}
+
+/// Called by `__hard_fault_handler` with the stack pointer that was active
+/// when the exception was taken
+#[cfg(all(feature = "rt", target_arch = "arm"))]
+unsafe extern "C" fn hard_fault_trampoline(frame: *const ExceptionFrame) -> ! {
+    let report = FaultReport::capture(&super::Peripherals::scb(), Some(&*frame));
+
+    if let Some(hook) = *core::ptr::addr_of!(HARD_FAULT_HOOK) {
+        hook(&report);
+    }
+
+    loop {
+        super::asm::nop();
+    }
+}
+
+// HardFault handler: pick MSP or PSP depending on EXC_RETURN[2]
+#[cfg(all(feature = "rt", target_arch = "arm"))]
+core::arch::global_asm!(
+    ".section .text.__hard_fault_handler,\"ax\",%progbits",
+    ".global __hard_fault_handler",
+    ".type __hard_fault_handler,%function",
+    ".thumb_func",
+    "__hard_fault_handler:",
+    "    tst lr, #4",
+    "    ite eq",
+    "    mrseq r0, msp",
+    "    mrsne r0, psp",
+    "    b {trampoline}",
+    trampoline = sym hard_fault_trampoline,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_mem_manage_fault() {
+        let mm = decode_mem_manage_fault(0x0000_0082, 0x2000_1000);
+        assert!(mm.data_access_violation);
+        assert!(!mm.instruction_access_violation);
+        assert_eq!(mm.address, Some(0x2000_1000));
+
+        let mm = decode_mem_manage_fault(0x0000_0011, 0x2000_1000);
+        assert!(mm.instruction_access_violation);
+        assert!(mm.stacking);
+        assert_eq!(mm.address, None);
+    }
+
+    #[test]
+    fn test_decode_bus_fault() {
+        let bf = decode_bus_fault(0x0000_8200, 0xdead_beef);
+        assert!(bf.precise_data_bus_error);
+        assert!(!bf.imprecise_data_bus_error);
+        assert_eq!(bf.address, Some(0xdead_beef));
+
+        let bf = decode_bus_fault(0x0000_0400, 0xdead_beef);
+        assert!(bf.imprecise_data_bus_error);
+        assert_eq!(bf.address, None);
+        assert!(!decode_mem_manage_fault(0x0000_0400, 0).is_active());
+    }
+
+    #[test]
+    fn test_decode_usage_fault() {
+        let uf = decode_usage_fault(0x0200_0000);
+        assert!(uf.divide_by_zero);
+        assert!(!uf.unaligned_access);
+
+        let uf = decode_usage_fault(0x0101_0000);
+        assert!(uf.undefined_instruction);
+        assert!(uf.unaligned_access);
+        assert!(!decode_bus_fault(0x0101_0000, 0).is_active());
+    }
+
+    #[test]
+    fn test_decode_hard_fault() {
+        let hf = decode_hard_fault(0x4000_0000);
+        assert!(hf.forced);
+        assert!(!hf.vector_table_read);
+        assert!(!hf.debug_event);
+
+        assert!(decode_hard_fault(0x0000_0002).vector_table_read);
+        assert!(!decode_hard_fault(0).is_active());
+    }
+
+    #[test]
+    fn test_decode_report() {
+        let report = FaultReport::decode(0x0200_0000, 0x4000_0000, 0, 0);
+
+        assert!(report.hard_fault.forced);
+        assert!(report.usage.divide_by_zero);
+        assert!(!report.mem_manage.is_active());
+        assert!(!report.bus.is_active());
+        assert_eq!(report.frame, None);
+    }
+}
@@ -0,0 +1,124 @@
+#![allow(dead_code)]
+
+use crate::peripheral;
+
+use super::asm;
+
+use self::register::*;
+
+pub use self::register::{ CoprocessorAccess, RoundingMode };
+
+mod register;
+
+/// Floating-point context control registers, CPACR lives in the SCB
+pub struct RegisterBlock {
+    /// Floating-point Context Control Register
+    fpccr: ContextControlRegister,
+
+    /// Floating-point Context Address Register
+    fpcar: ContextAddressRegister,
+
+    /// Floating-point Default Status Control Register
+    fpdscr: DefaultStatusControlRegister,
+}
+
+/// How the FP context is saved on exception entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatePreservation {
+    /// FP registers are never stacked, exception handlers must not use the FPU
+    Disabled,
+
+    /// FP registers are stacked on every exception entry that uses them
+    Automatic,
+
+    /// Stack space is reserved but registers are only saved on first FP use
+    Lazy,
+}
+
+fn cpacr() -> &'static mut CoprocessorAccessControlRegister {
+    peripheral(0xe000_ed88)
+}
+
+impl RegisterBlock {
+    /// Grants full access to CP10 and CP11
+    pub fn enable(&mut self) {
+        self.set_access(CoprocessorAccess::Full);
+    }
+
+    pub fn disable(&mut self) {
+        self.set_access(CoprocessorAccess::Denied);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        cpacr().cp10_get_access() != CoprocessorAccess::Denied
+    }
+
+    pub fn set_access(&mut self, access: CoprocessorAccess) {
+        let cpacr = cpacr();
+        cpacr.cp10_set_access(access);
+        cpacr.cp11_set_access(access);
+
+        asm::dsb();
+        asm::isb();
+    }
+
+    pub fn get_state_preservation(&self) -> StatePreservation {
+        match (
+            self.fpccr.automatic_state_preservation_is_enabled(),
+            self.fpccr.lazy_state_preservation_is_enabled(),
+        ) {
+            (false, _) => StatePreservation::Disabled,
+            (true, false) => StatePreservation::Automatic,
+            (true, true) => StatePreservation::Lazy,
+        }
+    }
+
+    pub fn set_state_preservation(&mut self, preservation: StatePreservation) {
+        let (aspen, lspen) = match preservation {
+            StatePreservation::Disabled => (false, false),
+            StatePreservation::Automatic => (true, false),
+            StatePreservation::Lazy => (true, true),
+        };
+
+        self.fpccr.set_automatic_state_preservation(aspen);
+        self.fpccr.set_lazy_state_preservation(lspen);
+    }
+
+    /// Returns `true` while lazy preservation of an FP context is pending
+    #[inline]
+    pub fn lazy_state_preservation_is_active(&self) -> bool {
+        self.fpccr.lazy_state_preservation_is_active()
+    }
+
+    /// Address of the reserved FP register space on the stack
+    #[inline]
+    pub fn context_address(&self) -> u32 {
+        self.fpcar.get_address() << 3
+    }
+
+    /// Rounding mode loaded into FPSCR for new floating-point contexts
+    #[inline]
+    pub fn set_default_rounding_mode(&mut self, mode: RoundingMode) {
+        self.fpdscr.set_rounding_mode(mode)
+    }
+
+    #[inline]
+    pub fn get_default_rounding_mode(&self) -> RoundingMode {
+        self.fpdscr.get_rounding_mode()
+    }
+
+    #[inline]
+    pub fn set_default_flush_to_zero(&mut self, enabled: bool) {
+        self.fpdscr.set_flush_to_zero(enabled)
+    }
+
+    #[inline]
+    pub fn set_default_nan(&mut self, enabled: bool) {
+        self.fpdscr.set_default_nan(enabled)
+    }
+
+    #[inline]
+    pub fn set_default_alternative_half_precision(&mut self, enabled: bool) {
+        self.fpdscr.set_alternative_half_precision(enabled)
+    }
+}
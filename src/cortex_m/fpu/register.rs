@@ -0,0 +1,117 @@
+use register::{ field::derive::RegisterField, register };
+
+#[register(u32)]
+pub(super) struct CoprocessorAccessControlRegister {
+    #[bits(20)]
+    __: u32,
+
+    #[bits(2, rw, get = cp10_get_access, set = cp10_set_access)]
+    pub(super) CP10: CoprocessorAccess,
+
+    #[bits(2, rw, get = cp11_get_access, set = cp11_set_access)]
+    pub(super) CP11: CoprocessorAccess,
+
+    #[bits(8)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct ContextControlRegister {
+    #[bits(1, r, get = lazy_state_preservation_is_active)]
+    pub(super) LSPACT: bool,
+
+    #[bits(1, r, get = is_allocated_in_user_mode)]
+    pub(super) USER: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, r, get = is_allocated_in_thread_mode)]
+    pub(super) THREAD: bool,
+
+    #[bits(1, r, get = hard_fault_is_ready)]
+    pub(super) HFRDY: bool,
+
+    #[bits(1, r, get = memory_management_fault_is_ready)]
+    pub(super) MMRDY: bool,
+
+    #[bits(1, r, get = bus_fault_is_ready)]
+    pub(super) BFRDY: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, r, get = debug_monitor_is_ready)]
+    pub(super) MONRDY: bool,
+
+    #[bits(21)]
+    __: u32,
+
+    #[bits(1, rw, get = lazy_state_preservation_is_enabled, set = set_lazy_state_preservation)]
+    pub(super) LSPEN: bool,
+
+    #[bits(
+        1,
+        rw,
+        get = automatic_state_preservation_is_enabled,
+        set = set_automatic_state_preservation
+    )]
+    pub(super) ASPEN: bool,
+}
+
+#[register(u32)]
+pub(super) struct ContextAddressRegister {
+    #[bits(3)]
+    __: u32,
+
+    #[bits(29, r, get = get_address)]
+    pub(super) ADDRESS: u32,
+}
+
+#[register(u32)]
+pub(super) struct DefaultStatusControlRegister {
+    #[bits(22)]
+    __: u32,
+
+    #[bits(2, rw, get = get_rounding_mode, set = set_rounding_mode)]
+    pub(super) RMode: RoundingMode,
+
+    #[bits(1, rw, get = flush_to_zero_is_enabled, set = set_flush_to_zero)]
+    pub(super) FZ: bool,
+
+    #[bits(1, rw, get = default_nan_is_enabled, set = set_default_nan)]
+    pub(super) DN: bool,
+
+    #[bits(
+        1,
+        rw,
+        get = alternative_half_precision_is_enabled,
+        set = set_alternative_half_precision
+    )]
+    pub(super) AHP: bool,
+
+    #[bits(5)]
+    __: u32,
+}
+
+#[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoprocessorAccess {
+    Denied = 0b00,
+    Privileged = 0b01,
+    Full = 0b11,
+}
+
+#[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Round to Nearest
+    Nearest = 0b00,
+
+    /// Round towards Plus Infinity
+    PlusInfinity = 0b01,
+
+    /// Round towards Minus Infinity
+    MinusInfinity = 0b10,
+
+    /// Round towards Zero
+    Zero = 0b11,
+}
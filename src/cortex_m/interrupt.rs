@@ -0,0 +1,266 @@
+//! Interrupt masking (PRIMASK, BASEPRI, FAULTMASK) and critical sections.
+//!
+//! On other architectures (e.g. when running host tests) the mask registers
+//! are emulated so code built on top of them keeps working.
+
+use core::marker::PhantomData;
+
+use super::NVIC_PRIO_BITS;
+
+/// Token proving that interrupts are masked for as long as it is alive
+pub struct CriticalSection<'cs> {
+    _marker: PhantomData<&'cs ()>,
+}
+
+impl<'cs> CriticalSection<'cs> {
+    /// # Safety
+    ///
+    /// Interrupts have to stay masked for the whole lifetime `'cs`
+    #[inline(always)]
+    pub unsafe fn new() -> Self {
+        Self { _marker: PhantomData }
+    }
+}
+
+#[cfg(not(target_arch = "arm"))]
+mod emulated {
+    use core::sync::atomic::{ AtomicBool, AtomicU8 };
+
+    pub(super) static PRIMASK: AtomicBool = AtomicBool::new(false);
+    pub(super) static FAULTMASK: AtomicBool = AtomicBool::new(false);
+    pub(super) static BASEPRI: AtomicU8 = AtomicU8::new(0);
+}
+
+/// Returns `true` if PRIMASK is set, i.e. all configurable interrupts are masked
+#[inline(always)]
+pub fn primask() -> bool {
+    #[cfg(target_arch = "arm")]
+    {
+        let r: u32;
+        unsafe {
+            core::arch::asm!(
+                "mrs {}, PRIMASK",
+                out(reg) r,
+                options(nomem, nostack, preserves_flags)
+            );
+        }
+        (r & 1) != 0
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    emulated::PRIMASK.load(core::sync::atomic::Ordering::SeqCst)
+}
+
+/// Masks all interrupts with configurable priority
+#[inline(always)]
+pub fn disable() {
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("cpsid i", options(nostack, preserves_flags));
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    emulated::PRIMASK.store(true, core::sync::atomic::Ordering::SeqCst);
+}
+
+/// Unmasks interrupts with configurable priority
+///
+/// # Safety
+///
+/// Must not be called from within a critical section
+#[inline(always)]
+pub unsafe fn enable() {
+    #[cfg(target_arch = "arm")]
+    core::arch::asm!("cpsie i", options(nostack, preserves_flags));
+
+    #[cfg(not(target_arch = "arm"))]
+    emulated::PRIMASK.store(false, core::sync::atomic::Ordering::SeqCst);
+}
+
+/// Returns `true` if FAULTMASK is set, i.e. everything but NMI is masked
+#[inline(always)]
+pub fn faultmask() -> bool {
+    #[cfg(target_arch = "arm")]
+    {
+        let r: u32;
+        unsafe {
+            core::arch::asm!(
+                "mrs {}, FAULTMASK",
+                out(reg) r,
+                options(nomem, nostack, preserves_flags)
+            );
+        }
+        (r & 1) != 0
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    emulated::FAULTMASK.load(core::sync::atomic::Ordering::SeqCst)
+}
+
+/// Masks all exceptions except NMI
+#[inline(always)]
+pub fn disable_faults() {
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("cpsid f", options(nostack, preserves_flags));
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    emulated::FAULTMASK.store(true, core::sync::atomic::Ordering::SeqCst);
+}
+
+/// # Safety
+///
+/// Must not be called from within a section relying on FAULTMASK
+#[inline(always)]
+pub unsafe fn enable_faults() {
+    #[cfg(target_arch = "arm")]
+    core::arch::asm!("cpsie f", options(nostack, preserves_flags));
+
+    #[cfg(not(target_arch = "arm"))]
+    emulated::FAULTMASK.store(false, core::sync::atomic::Ordering::SeqCst);
+}
+
+/// Raw BASEPRI value, `0` means no masking
+#[inline(always)]
+pub fn basepri() -> u8 {
+    #[cfg(target_arch = "arm")]
+    {
+        let r: u32;
+        unsafe {
+            core::arch::asm!(
+                "mrs {}, BASEPRI",
+                out(reg) r,
+                options(nomem, nostack, preserves_flags)
+            );
+        }
+        r as u8
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    emulated::BASEPRI.load(core::sync::atomic::Ordering::SeqCst)
+}
+
+/// Writes the raw BASEPRI value
+///
+/// # Safety
+///
+/// Lowering BASEPRI can break priority-ceiling sections that are still running
+#[inline(always)]
+pub unsafe fn set_basepri(basepri: u8) {
+    #[cfg(target_arch = "arm")]
+    core::arch::asm!("msr BASEPRI, {}", in(reg) basepri as u32, options(nostack, preserves_flags));
+
+    #[cfg(not(target_arch = "arm"))]
+    emulated::BASEPRI.store(basepri, core::sync::atomic::Ordering::SeqCst);
+}
+
+/// Writes BASEPRI only if it raises the masking level
+#[inline(always)]
+pub fn set_basepri_max(basepri: u8) {
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!(
+            "msr BASEPRI_MAX, {}",
+            in(reg) basepri as u32,
+            options(nostack, preserves_flags)
+        );
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    {
+        let current = basepri();
+        if basepri != 0 && (current == 0 || basepri < current) {
+            emulated::BASEPRI.store(basepri, core::sync::atomic::Ordering::SeqCst);
+        }
+    }
+}
+
+/// Runs `f` with all configurable interrupts masked.
+///
+/// Nests correctly: PRIMASK is only cleared again if it was clear on entry.
+#[inline]
+pub fn free<F, R>(f: F) -> R where F: FnOnce(&CriticalSection) -> R {
+    let masked = primask();
+
+    disable();
+
+    let r = f(unsafe { &CriticalSection::new() });
+
+    if !masked {
+        unsafe { enable() }
+    }
+
+    r
+}
+
+/// Runs `f` with every interrupt of priority `ceiling` or lower urgency masked.
+///
+/// Interrupts with a more urgent (numerically lower) priority keep running.
+/// `ceiling` uses the same `NVIC_PRIO_BITS` wide encoding as
+/// `NVIC::irq_set_priority`, a ceiling of `0` masks everything via PRIMASK.
+/// Panics if `ceiling` does not fit in `NVIC_PRIO_BITS` bits.
+#[inline]
+pub fn lock<F, R>(ceiling: u8, f: F) -> R where F: FnOnce() -> R {
+    if ceiling == 0 {
+        return free(|_| f());
+    }
+
+    let old = basepri();
+
+    set_basepri_max(basepri_value(ceiling));
+
+    let r = f();
+
+    unsafe { set_basepri(old) }
+
+    r
+}
+
+/// BASEPRI value masking priority `ceiling` and lower urgency, the priority
+/// sits in the upper `NVIC_PRIO_BITS` bits
+#[inline]
+fn basepri_value(ceiling: u8) -> u8 {
+    assert!(ceiling < 1 << NVIC_PRIO_BITS, "priority ceiling out of range");
+
+    ceiling << (8 - NVIC_PRIO_BITS)
+}
+
+#[cfg(feature = "critical-section-single-core")]
+mod single_core {
+    struct SingleCoreCriticalSection;
+
+    critical_section::set_impl!(SingleCoreCriticalSection);
+
+    unsafe impl critical_section::Impl for SingleCoreCriticalSection {
+        unsafe fn acquire() -> critical_section::RawRestoreState {
+            let masked = super::primask();
+            super::disable();
+            masked
+        }
+
+        unsafe fn release(masked: critical_section::RawRestoreState) {
+            if !masked {
+                super::enable();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basepri_value() {
+        assert_eq!(basepri_value(1), 0x10);
+        assert_eq!(basepri_value(5), 0x50);
+        assert_eq!(basepri_value(15), 0xf0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_basepri_value_out_of_range() {
+        basepri_value(16);
+    }
+}
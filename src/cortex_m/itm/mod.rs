@@ -0,0 +1,172 @@
+#![allow(dead_code)]
+
+use core::{ fmt, ptr };
+
+use self::register::*;
+
+mod register;
+
+pub struct RegisterBlock {
+    /// Stimulus Port Registers
+    stim: [u32; 256],
+
+    __reserved0: [u32; 640],
+
+    /// Trace Enable Registers
+    ter: [u32; 8],
+
+    __reserved1: [u32; 8],
+
+    /// Trace Privilege Register
+    tpr: u32,
+
+    __reserved2: [u32; 15],
+
+    /// Trace Control Register
+    tcr: TraceControlRegister,
+
+    __reserved3: [u32; 75],
+
+    /// Lock Access Register
+    lar: u32,
+}
+
+impl RegisterBlock {
+    /// Number of stimulus ports
+    pub const PORTS: usize = 256;
+
+    /// Key that unlocks write access to the ITM registers
+    const LAR_KEY: u32 = 0xc5ac_ce55;
+
+    /// Enables write access to the ITM registers
+    #[inline]
+    pub fn unlock(&mut self) {
+        unsafe { ptr::write_volatile(&mut self.lar, Self::LAR_KEY) }
+    }
+
+    /// Enables the ITM with DWT packet forwarding.
+    ///
+    /// Trace has to be enabled in the DCB and `trace_bus_id` must not be
+    /// used by any other trace source.
+    pub fn enable(&mut self, trace_bus_id: u8) {
+        self.unlock();
+
+        self.tcr.set_trace_bus_id(trace_bus_id);
+        self.tcr.enable_sync();
+        self.tcr.enable_dwt();
+        self.tcr.enable();
+    }
+
+    pub fn disable(&mut self) {
+        while self.tcr.is_busy() {}
+
+        self.tcr.disable();
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.tcr.is_enabled()
+    }
+
+    pub fn enable_port(&mut self, port: usize) {
+        let ter = &mut self.ter[port / 32];
+        unsafe { ptr::write_volatile(ter, ptr::read_volatile(ter) | (1 << (port % 32))) }
+    }
+
+    pub fn disable_port(&mut self, port: usize) {
+        let ter = &mut self.ter[port / 32];
+        unsafe { ptr::write_volatile(ter, ptr::read_volatile(ter) & !(1 << (port % 32))) }
+    }
+
+    /// Returns `true` if the ITM and stimulus `port` are both enabled
+    pub fn port_is_enabled(&self, port: usize) -> bool {
+        let ter = unsafe { ptr::read_volatile(&self.ter[port / 32]) };
+        self.is_enabled() && (ter & (1 << (port % 32))) != 0
+    }
+
+    /// Returns a writer for stimulus `port`
+    #[inline]
+    pub fn port(&mut self, port: usize) -> Stimulus<'_> {
+        let enabled = self.port_is_enabled(port);
+
+        Stimulus { stim: &mut self.stim[port], enabled }
+    }
+
+    #[inline]
+    pub fn write_u8(&mut self, port: usize, value: u8) {
+        self.port(port).write_u8(value)
+    }
+
+    #[inline]
+    pub fn write_u16(&mut self, port: usize, value: u16) {
+        self.port(port).write_u16(value)
+    }
+
+    #[inline]
+    pub fn write_u32(&mut self, port: usize, value: u32) {
+        self.port(port).write_u32(value)
+    }
+
+    #[inline]
+    pub fn write_bytes(&mut self, port: usize, bytes: &[u8]) {
+        self.port(port).write_bytes(bytes)
+    }
+}
+
+/// Stimulus port writer.
+///
+/// Output to a disabled port is dropped, so logging is harmless when no trace
+/// probe is attached.
+pub struct Stimulus<'a> {
+    stim: &'a mut u32,
+    enabled: bool,
+}
+
+impl<'a> Stimulus<'a> {
+    /// Waits until the port FIFO can accept another write
+    #[inline]
+    fn wait(&self) {
+        while unsafe { ptr::read_volatile(&*self.stim) } & 1 == 0 {}
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        if self.enabled {
+            self.wait();
+            unsafe { ptr::write_volatile(&mut *self.stim as *mut u32 as *mut u8, value) }
+        }
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        if self.enabled {
+            self.wait();
+            unsafe { ptr::write_volatile(&mut *self.stim as *mut u32 as *mut u16, value) }
+        }
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        if self.enabled {
+            self.wait();
+            unsafe { ptr::write_volatile(&mut *self.stim as *mut u32, value) }
+        }
+    }
+
+    /// Writes `bytes` a word at a time, the tail is sent byte by byte
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(4);
+
+        for chunk in &mut chunks {
+            self.write_u32(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+        }
+
+        for &b in chunks.remainder() {
+            self.write_u8(b);
+        }
+    }
+}
+
+impl<'a> fmt::Write for Stimulus<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
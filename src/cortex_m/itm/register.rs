@@ -0,0 +1,43 @@
+use register::register;
+
+#[register(u32)]
+pub(super) struct TraceControlRegister {
+    #[bits(1, rwc, get = is_enabled, set = enable, clear = disable)]
+    pub(super) ITMENA: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = timestamps_is_enabled,
+        set = enable_timestamps,
+        clear = disable_timestamps
+    )]
+    pub(super) TSENA: bool,
+
+    #[bits(1, rwc, get = sync_is_enabled, set = enable_sync, clear = disable_sync)]
+    pub(super) SYNCENA: bool,
+
+    #[bits(1, rwc, get = dwt_is_enabled, set = enable_dwt, clear = disable_dwt)]
+    pub(super) TXENA: bool,
+
+    #[bits(1, rwc, get = swo_is_enabled, set = enable_swo, clear = disable_swo)]
+    pub(super) SWOENA: bool,
+
+    #[bits(3)]
+    __: u32,
+
+    #[bits(2, rw, get = get_timestamp_prescaler, set = set_timestamp_prescaler)]
+    pub(super) TSPRESCALE: u8,
+
+    #[bits(6)]
+    __: u32,
+
+    #[bits(7, rw, get = get_trace_bus_id, set = set_trace_bus_id)]
+    pub(super) TRACEBUSID: u8,
+
+    #[bits(1, r, get = is_busy)]
+    pub(super) BUSY: bool,
+
+    #[bits(8)]
+    __: u32,
+}
@@ -1,7 +1,22 @@
 use core::{ marker::PhantomData, ops::{ Deref, DerefMut } };
 
+pub mod asm;
+pub mod dcb;
+pub mod dwt;
+pub mod fault;
+pub mod fpu;
+pub mod interrupt;
+pub mod itm;
+pub mod mpu;
 pub mod nvic;
+pub mod power;
+#[cfg(all(feature = "rt", target_arch = "arm"))]
+pub mod rt;
 pub mod scb;
+pub mod syst;
+
+/// Number of priority bits implemented in the NVIC and SCB
+pub const NVIC_PRIO_BITS: u8 = 4;
 
 pub struct Peripherals {}
 
@@ -13,6 +28,30 @@ impl Peripherals {
     pub fn scb() -> SCB {
         SCB::default()
     }
+
+    pub fn syst() -> SYST {
+        SYST::default()
+    }
+
+    pub fn mpu() -> MPU {
+        MPU::default()
+    }
+
+    pub fn fpu() -> FPU {
+        FPU::default()
+    }
+
+    pub fn dcb() -> DCB {
+        DCB::default()
+    }
+
+    pub fn dwt() -> DWT {
+        DWT::default()
+    }
+
+    pub fn itm() -> ITM {
+        ITM::default()
+    }
 }
 
 #[derive(Default)]
@@ -62,3 +101,147 @@ impl DerefMut for SCB {
         unsafe { &mut *(Self::PTR as *mut _) }
     }
 }
+
+#[derive(Default)]
+pub struct SYST {
+    _marker: PhantomData<*const ()>,
+}
+
+impl SYST {
+    pub const PTR: u32 = 0xe000_e010;
+}
+
+impl Deref for SYST {
+    type Target = syst::RegisterBlock;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(Self::PTR as *const _) }
+    }
+}
+
+impl DerefMut for SYST {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *(Self::PTR as *mut _) }
+    }
+}
+
+#[derive(Default)]
+pub struct MPU {
+    _marker: PhantomData<*const ()>,
+}
+
+impl MPU {
+    pub const PTR: u32 = 0xe000_ed90;
+}
+
+impl Deref for MPU {
+    type Target = mpu::RegisterBlock;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(Self::PTR as *const _) }
+    }
+}
+
+impl DerefMut for MPU {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *(Self::PTR as *mut _) }
+    }
+}
+
+#[derive(Default)]
+pub struct FPU {
+    _marker: PhantomData<*const ()>,
+}
+
+impl FPU {
+    pub const PTR: u32 = 0xe000_ef34;
+}
+
+impl Deref for FPU {
+    type Target = fpu::RegisterBlock;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(Self::PTR as *const _) }
+    }
+}
+
+impl DerefMut for FPU {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *(Self::PTR as *mut _) }
+    }
+}
+
+#[derive(Default)]
+pub struct DCB {
+    _marker: PhantomData<*const ()>,
+}
+
+impl DCB {
+    pub const PTR: u32 = 0xe000_edf0;
+}
+
+impl Deref for DCB {
+    type Target = dcb::RegisterBlock;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(Self::PTR as *const _) }
+    }
+}
+
+impl DerefMut for DCB {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *(Self::PTR as *mut _) }
+    }
+}
+
+#[derive(Default)]
+pub struct DWT {
+    _marker: PhantomData<*const ()>,
+}
+
+impl DWT {
+    pub const PTR: u32 = 0xe000_1000;
+}
+
+impl Deref for DWT {
+    type Target = dwt::RegisterBlock;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(Self::PTR as *const _) }
+    }
+}
+
+impl DerefMut for DWT {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *(Self::PTR as *mut _) }
+    }
+}
+
+#[derive(Default)]
+pub struct ITM {
+    _marker: PhantomData<*const ()>,
+}
+
+impl ITM {
+    pub const PTR: u32 = 0xe000_0000;
+}
+
+impl Deref for ITM {
+    type Target = itm::RegisterBlock;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(Self::PTR as *const _) }
+    }
+}
+
+impl DerefMut for ITM {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *(Self::PTR as *mut _) }
+    }
+}
@@ -0,0 +1,343 @@
+#![allow(dead_code)]
+
+use core::fmt;
+
+use super::asm;
+
+use self::register::*;
+
+pub use self::register::AccessPermission;
+
+mod register;
+
+pub struct RegisterBlock {
+    /// MPU Type Register
+    typer: TypeRegister,
+
+    /// MPU Control Register
+    ctrl: ControlRegister,
+
+    /// MPU Region Number Register
+    rnr: RegionNumberRegister,
+
+    /// MPU Region Base Address Register
+    rbar: RegionBaseAddressRegister,
+
+    /// MPU Region Attribute and Size Register
+    rasr: RegionAttributeAndSizeRegister,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Region size is not a power of two or smaller than 32 bytes
+    InvalidSize(u32),
+
+    /// Base address is not aligned to the region size
+    UnalignedBase(u32),
+
+    /// Subregions can only be disabled in regions of 256 bytes or more
+    SubregionsNotSupported,
+
+    /// Region number is not implemented
+    InvalidRegion(u8),
+
+    /// Stack guard does not fit below the given stack bottom
+    OutOfRange(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidSize(s) => f.write_fmt(format_args!("Invalid region size: {}", s)),
+            Error::UnalignedBase(a) => f.write_fmt(format_args!("Unaligned base: {:#010x}", a)),
+            Error::SubregionsNotSupported => f.write_str("Subregions not supported"),
+            Error::InvalidRegion(r) => f.write_fmt(format_args!("Invalid region: {}", r)),
+            Error::OutOfRange(a) =>
+                f.write_fmt(format_args!("No room for the guard below {:#010x}", a)),
+        }
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    NonCacheable,
+    WriteThrough,
+    WriteBack,
+    WriteBackWriteAllocate,
+}
+
+/// Memory type of a region, encoded into the TEX, C, B and S bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryAttributes {
+    StronglyOrdered,
+    Device {
+        shareable: bool,
+    },
+    Normal {
+        policy: CachePolicy,
+        shareable: bool,
+    },
+    Raw {
+        tex: u8,
+        c: bool,
+        b: bool,
+        s: bool,
+    },
+}
+
+impl MemoryAttributes {
+    /// Returns the (TEX, C, B, S) encoding
+    pub fn encode(self) -> (u8, bool, bool, bool) {
+        match self {
+            MemoryAttributes::StronglyOrdered => (0b000, false, false, true),
+            MemoryAttributes::Device { shareable: true } => (0b000, false, true, true),
+            MemoryAttributes::Device { shareable: false } => (0b010, false, false, false),
+            MemoryAttributes::Normal { policy, shareable } =>
+                match policy {
+                    CachePolicy::NonCacheable => (0b001, false, false, shareable),
+                    CachePolicy::WriteThrough => (0b000, true, false, shareable),
+                    CachePolicy::WriteBack => (0b000, true, true, shareable),
+                    CachePolicy::WriteBackWriteAllocate => (0b001, true, true, shareable),
+                }
+            MemoryAttributes::Raw { tex, c, b, s } => (tex & 0b111, c, b, s),
+        }
+    }
+}
+
+/// MPU region description, validated when it is constructed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    base: u32,
+    size: u32,
+    subregions_disabled: u8,
+    access: AccessPermission,
+    execute_never: bool,
+    attributes: MemoryAttributes,
+}
+
+impl Region {
+    /// Smallest region supported by the MPU
+    pub const MIN_SIZE: u32 = 32;
+
+    /// Creates a full access, executable, normal write-back region.
+    ///
+    /// `size` has to be a power of two of at least 32 bytes and `base` has to
+    /// be aligned to it.
+    pub fn new(base: u32, size: u32) -> Result<Self> {
+        if size < Self::MIN_SIZE || !size.is_power_of_two() {
+            return Err(Error::InvalidSize(size));
+        }
+
+        if base & (size - 1) != 0 {
+            return Err(Error::UnalignedBase(base));
+        }
+
+        Ok(Self {
+            base,
+            size,
+            subregions_disabled: 0,
+            access: AccessPermission::ReadWrite,
+            execute_never: false,
+            attributes: MemoryAttributes::Normal {
+                policy: CachePolicy::WriteBack,
+                shareable: false,
+            },
+        })
+    }
+
+    /// Creates a no-access, non-executable region of `size` bytes ending right
+    /// below `stack_bottom`, the lowest address of a full-descending stack
+    pub fn stack_guard(stack_bottom: u32, size: u32) -> Result<Self> {
+        let base = stack_bottom.checked_sub(size).ok_or(Error::OutOfRange(stack_bottom))?;
+
+        Ok(
+            Self::new(base, size)?
+                .access(AccessPermission::NoAccess)
+                .execute_never(true)
+                .attributes(MemoryAttributes::Normal {
+                    policy: CachePolicy::NonCacheable,
+                    shareable: false,
+                })
+        )
+    }
+
+    /// Disables the subregions whose bit is set in `mask`, each subregion
+    /// covers an eighth of the region
+    pub fn disable_subregions(mut self, mask: u8) -> Result<Self> {
+        if mask != 0 && self.size < 256 {
+            return Err(Error::SubregionsNotSupported);
+        }
+
+        self.subregions_disabled = mask;
+
+        Ok(self)
+    }
+
+    pub fn access(mut self, access: AccessPermission) -> Self {
+        self.access = access;
+        self
+    }
+
+    pub fn execute_never(mut self, xn: bool) -> Self {
+        self.execute_never = xn;
+        self
+    }
+
+    pub fn attributes(mut self, attributes: MemoryAttributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    #[inline]
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    #[inline]
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Value of the RASR SIZE field, region size is 2^(SIZE + 1)
+    #[inline]
+    fn size_field(&self) -> u32 {
+        self.size.trailing_zeros() - 1
+    }
+
+    /// Raw RASR value with the region enabled
+    pub fn rasr(&self) -> u32 {
+        let (tex, c, b, s) = self.attributes.encode();
+
+        1 |
+            (self.size_field() << 1) |
+            ((self.subregions_disabled as u32) << 8) |
+            ((b as u32) << 16) |
+            ((c as u32) << 17) |
+            ((s as u32) << 18) |
+            ((tex as u32) << 19) |
+            ((self.access as u32) << 24) |
+            ((self.execute_never as u32) << 28)
+    }
+}
+
+impl RegisterBlock {
+    /// Number of implemented data regions
+    #[inline]
+    pub fn regions(&self) -> u8 {
+        self.typer.get_data_region_count()
+    }
+
+    /// Enables the MPU.
+    ///
+    /// With `privileged_default` set, privileged accesses that hit no region
+    /// use the default memory map instead of faulting.
+    pub fn enable(&mut self, privileged_default: bool) {
+        if privileged_default {
+            self.ctrl.enable_default_map();
+        } else {
+            self.ctrl.disable_default_map();
+        }
+        self.ctrl.enable();
+
+        asm::dsb();
+        asm::isb();
+    }
+
+    pub fn disable(&mut self) {
+        asm::dmb();
+
+        self.ctrl.disable();
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.ctrl.is_enabled()
+    }
+
+    pub fn configure_region(&mut self, number: u8, region: &Region) -> Result<()> {
+        if number >= self.regions() {
+            return Err(Error::InvalidRegion(number));
+        }
+
+        self.rnr.set_region(number);
+        self.rbar.set(region.base);
+        self.rasr.set(region.rasr());
+
+        Ok(())
+    }
+
+    pub fn disable_region(&mut self, number: u8) -> Result<()> {
+        if number >= self.regions() {
+            return Err(Error::InvalidRegion(number));
+        }
+
+        self.rnr.set_region(number);
+        self.rasr.disable_region();
+
+        Ok(())
+    }
+
+    /// Places a no-access region of `size` bytes below `stack_bottom` so a
+    /// stack overflow raises a MemManage fault instead of corrupting memory
+    pub fn set_stack_guard(&mut self, number: u8, stack_bottom: u32, size: u32) -> Result<()> {
+        let region = Region::stack_guard(stack_bottom, size)?;
+        self.configure_region(number, &region)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_validation() {
+        assert_eq!(Region::new(0x2000_0000, 16), Err(Error::InvalidSize(16)));
+        assert_eq!(Region::new(0x2000_0000, 96), Err(Error::InvalidSize(96)));
+        assert_eq!(Region::new(0x2000_0100, 1024), Err(Error::UnalignedBase(0x2000_0100)));
+        assert!(Region::new(0x2000_0400, 1024).is_ok());
+
+        let region = Region::new(0x2000_0000, 128).unwrap();
+        assert_eq!(region.disable_subregions(0x01), Err(Error::SubregionsNotSupported));
+        assert!(region.disable_subregions(0x00).is_ok());
+    }
+
+    #[test]
+    fn test_region_rasr() {
+        // 128 KiB SRAM, full access, write-back, two subregions disabled
+        let region = Region::new(0x2000_0000, 128 * 1024)
+            .unwrap()
+            .disable_subregions(0b1000_0001)
+            .unwrap();
+        assert_eq!(region.rasr(), 0x0303_8121);
+
+        // 1 MiB flash, read-only, write-through
+        let region = Region::new(0x0800_0000, 1024 * 1024)
+            .unwrap()
+            .access(AccessPermission::ReadOnly)
+            .attributes(MemoryAttributes::Normal {
+                policy: CachePolicy::WriteThrough,
+                shareable: false,
+            });
+        assert_eq!(region.rasr(), 0x0602_0027);
+
+        // peripherals, device memory, execute never
+        let region = Region::new(0x4000_0000, 512 * 1024 * 1024)
+            .unwrap()
+            .execute_never(true)
+            .attributes(MemoryAttributes::Device { shareable: true });
+        assert_eq!(region.rasr(), 0x1305_0039);
+    }
+
+    #[test]
+    fn test_stack_guard() {
+        let region = Region::stack_guard(0x2000_1000, 256).unwrap();
+        assert_eq!(region.base(), 0x2000_0f00);
+        assert_eq!(region.size(), 256);
+        assert_eq!(region.rasr(), 0x1008_000f);
+
+        assert_eq!(Region::stack_guard(0x2000_1080, 256), Err(Error::UnalignedBase(0x2000_0f80)));
+        assert_eq!(Region::stack_guard(0x10, 256), Err(Error::OutOfRange(0x10)));
+    }
+}
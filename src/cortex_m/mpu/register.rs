@@ -0,0 +1,130 @@
+use register::{ field::derive::RegisterField, register };
+
+#[register(u32)]
+pub(super) struct TypeRegister {
+    #[bits(1, r, get = has_separate_maps)]
+    pub(super) SEPARATE: bool,
+
+    #[bits(7)]
+    __: u32,
+
+    #[bits(8, r, get = get_data_region_count)]
+    pub(super) DREGION: u8,
+
+    #[bits(8, r, get = get_instruction_region_count)]
+    pub(super) IREGION: u8,
+
+    #[bits(8)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct ControlRegister {
+    #[bits(1, rwc, get = is_enabled, set = enable, clear = disable)]
+    pub(super) ENABLE: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = is_enabled_during_fault,
+        set = enable_during_fault,
+        clear = disable_during_fault
+    )]
+    pub(super) HFNMIENA: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = default_map_is_enabled,
+        set = enable_default_map,
+        clear = disable_default_map
+    )]
+    pub(super) PRIVDEFENA: bool,
+
+    #[bits(29)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct RegionNumberRegister {
+    #[bits(8, rw, get = get_region, set = set_region)]
+    pub(super) REGION: u8,
+
+    #[bits(24)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct RegionBaseAddressRegister {
+    #[bits(4, rw, get = get_region, set = set_region)]
+    pub(super) REGION: u8,
+
+    #[bits(1, rw, get = is_region_valid, set = set_region_valid)]
+    pub(super) VALID: bool,
+
+    #[bits(27, rw, get = get_address, set = set_address)]
+    pub(super) ADDR: u32,
+}
+
+#[register(u32)]
+pub(super) struct RegionAttributeAndSizeRegister {
+    #[bits(1, rwc, get = region_is_enabled, set = enable_region, clear = disable_region)]
+    pub(super) ENABLE: bool,
+
+    #[bits(5, rw, get = get_size, set = set_size)]
+    pub(super) SIZE: u8,
+
+    #[bits(2)]
+    __: u32,
+
+    #[bits(8, rw, get = get_subregions_disabled, set = set_subregions_disabled)]
+    pub(super) SRD: u8,
+
+    #[bits(1, rw, get = is_bufferable, set = set_bufferable)]
+    pub(super) B: bool,
+
+    #[bits(1, rw, get = is_cacheable, set = set_cacheable)]
+    pub(super) C: bool,
+
+    #[bits(1, rw, get = is_shareable, set = set_shareable)]
+    pub(super) S: bool,
+
+    #[bits(3, rw, get = get_type_extension, set = set_type_extension)]
+    pub(super) TEX: u8,
+
+    #[bits(2)]
+    __: u32,
+
+    #[bits(3, rw, get = get_access_permission, set = set_access_permission)]
+    pub(super) AP: AccessPermission,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, rw, get = is_execute_never, set = set_execute_never)]
+    pub(super) XN: bool,
+
+    #[bits(3)]
+    __: u32,
+}
+
+#[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessPermission {
+    /// Any access generates a permission fault
+    NoAccess = 0b000,
+
+    /// Privileged read/write, unprivileged no access
+    PrivilegedReadWrite = 0b001,
+
+    /// Privileged read/write, unprivileged read-only
+    PrivilegedReadWriteUnprivilegedReadOnly = 0b010,
+
+    /// Full access
+    ReadWrite = 0b011,
+
+    /// Privileged read-only, unprivileged no access
+    PrivilegedReadOnly = 0b101,
+
+    /// Privileged and unprivileged read-only
+    ReadOnly = 0b110,
+}
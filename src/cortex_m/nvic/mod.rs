@@ -1,5 +1,7 @@
 use crate::peripheral;
 
+use super::{ scb::PriorityGrouping, NVIC_PRIO_BITS };
+
 pub struct NVIC {
     /// Interrupt Set-Enable Register
     pub iser: [u32; 8],
@@ -26,48 +28,213 @@ pub struct NVIC {
 
     __reserved4: [u32; 56],
 
-    /// Interrupt Priority Register
-    pub ipr: [u32; 60],
+    /// Interrupt Priority Registers, one byte per interrupt
+    pub ipr: [u8; 240],
 
     __reserved5: [u32; 644],
 
     pub stir: u32,
 }
 
+/// Maps an interrupt source to its position in the device vector table
+pub trait InterruptNumber: Copy {
+    fn number(self) -> u16;
+}
+
+// ISER, ICER, ISPR and ICPR ignore zero bits and read back the enabled or
+// pending state, so the setters below write the single bit instead of doing a
+// read-modify-write that would also hit the other interrupts of the word
 impl NVIC {
-    pub fn irq_is_enabled(&mut self, irqn: usize) -> bool {
+    pub fn irq_is_enabled(&mut self, irq: impl InterruptNumber) -> bool {
+        let irqn = irq.number() as usize;
         (self.iser[irqn / 32] & (1 << irqn % 32)) != 0
     }
 
-    pub fn irq_enable(&mut self, irqn: usize) {
-        self.iser[irqn / 32] |= 1 << irqn % 32;
+    pub fn irq_enable(&mut self, irq: impl InterruptNumber) {
+        let irqn = irq.number() as usize;
+        self.iser[irqn / 32] = 1 << irqn % 32;
     }
 
-    pub fn irq_disable(&mut self, irqn: usize) {
-        self.icer[irqn / 32] |= 1 << irqn % 32;
+    pub fn irq_disable(&mut self, irq: impl InterruptNumber) {
+        let irqn = irq.number() as usize;
+        self.icer[irqn / 32] = 1 << irqn % 32;
     }
 
-    pub fn irq_is_active(&mut self, irqn: usize) -> bool {
+    pub fn irq_is_active(&mut self, irq: impl InterruptNumber) -> bool {
+        let irqn = irq.number() as usize;
         (self.iabr[irqn / 32] & (1 << irqn % 32)) != 0
     }
 
-    pub fn irq_set_pending(&mut self, irqn: usize) {
-        self.ispr[irqn / 32] |= 1 << irqn % 32;
+    pub fn irq_set_pending(&mut self, irq: impl InterruptNumber) {
+        let irqn = irq.number() as usize;
+        self.ispr[irqn / 32] = 1 << irqn % 32;
+    }
+
+    pub fn irq_clear_pending(&mut self, irq: impl InterruptNumber) {
+        let irqn = irq.number() as usize;
+        self.icpr[irqn / 32] = 1 << irqn % 32;
     }
 
-    pub fn irq_clear_pending(&mut self, irqn: usize) {
-        self.icpr[irqn / 32] |= 1 << irqn % 32;
+    /// Sets the priority of `irq`, only the lower `NVIC_PRIO_BITS` bits are used
+    pub fn irq_set_priority(&mut self, irq: impl InterruptNumber, priority: u8) {
+        let irqn = irq.number() as usize;
+        self.ipr[irqn] = priority << (8 - NVIC_PRIO_BITS);
     }
 
-    pub fn irq_set_priority(&mut self, irqn: usize, priority: u8) {
-        self.ipr[irqn / 4] |= (priority as u32) << (8 * (irqn % 4) + 4);
+    pub fn irq_get_priority(&self, irq: impl InterruptNumber) -> u8 {
+        let irqn = irq.number() as usize;
+        self.ipr[irqn] >> (8 - NVIC_PRIO_BITS)
     }
 
-    pub fn irq_trigger(&mut self, irqn: usize) {
-        self.stir = irqn as _;
+    pub fn irq_trigger(&mut self, irq: impl InterruptNumber) {
+        self.stir = irq.number() as _;
+    }
+}
+
+impl Default for NVIC {
+    fn default() -> Self {
+        Self {
+            iser: [0; 8],
+            __reserved0: [0; 24],
+            icer: [0; 8],
+            __reserved1: [0; 24],
+            ispr: [0; 8],
+            __reserved2: [0; 24],
+            icpr: [0; 8],
+            __reserved3: [0; 24],
+            iabr: [0; 8],
+            __reserved4: [0; 56],
+            ipr: [0; 240],
+            __reserved5: [0; 644],
+            stir: 0,
+        }
     }
 }
 
 pub fn nvic() -> &'static mut NVIC {
     peripheral(0xe000_e100)
 }
+
+/// Number of implemented group priority bits for the given grouping
+#[inline]
+fn preempt_bits(grouping: PriorityGrouping) -> u8 {
+    (7 - (grouping as u8)).min(NVIC_PRIO_BITS)
+}
+
+/// Combines a group (pre-emption) priority and a subpriority into a value
+/// suitable for [`NVIC::irq_set_priority`] according to the AIRCR PRIGROUP
+/// setting. Bits that do not fit into the respective field are dropped.
+pub fn encode_priority(grouping: PriorityGrouping, preempt: u8, sub: u8) -> u8 {
+    let preempt_bits = preempt_bits(grouping);
+    let sub_bits = NVIC_PRIO_BITS - preempt_bits;
+
+    let preempt = preempt & ((1u8 << preempt_bits) - 1);
+    let sub = sub & ((1u8 << sub_bits) - 1);
+
+    (preempt << sub_bits) | sub
+}
+
+/// Splits a priority into its group (pre-emption) priority and subpriority
+pub fn decode_priority(grouping: PriorityGrouping, priority: u8) -> (u8, u8) {
+    let sub_bits = NVIC_PRIO_BITS - preempt_bits(grouping);
+    let priority = priority & ((1u8 << NVIC_PRIO_BITS) - 1);
+
+    (priority >> sub_bits, priority & ((1u8 << sub_bits) - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interrupt::Interrupt;
+
+    use super::*;
+
+    #[test]
+    fn test_irq_set_priority() {
+        let mut nvic = NVIC::default();
+
+        nvic.irq_set_priority(Interrupt::USART1, 0xf);
+        assert_eq!(nvic.ipr[37], 0xf0);
+        assert_eq!(nvic.irq_get_priority(Interrupt::USART1), 0xf);
+
+        // lowering the number must not keep stale bits
+        nvic.irq_set_priority(Interrupt::USART1, 0x2);
+        assert_eq!(nvic.ipr[37], 0x20);
+        assert_eq!(nvic.irq_get_priority(Interrupt::USART1), 0x2);
+
+        // neighbours sharing the same IPR word are left alone
+        nvic.irq_set_priority(Interrupt::USART2, 0x5);
+        nvic.irq_set_priority(Interrupt::SPI2, 0x7);
+        assert_eq!(nvic.ipr[36], 0x70);
+        assert_eq!(nvic.ipr[37], 0x20);
+        assert_eq!(nvic.ipr[38], 0x50);
+        assert_eq!(nvic.ipr[39], 0x00);
+
+        nvic.irq_set_priority(Interrupt::DMA2D, 0x3);
+        assert_eq!(nvic.ipr[90], 0x30);
+        assert_eq!(nvic.irq_get_priority(Interrupt::DMA2D), 0x3);
+    }
+
+    #[test]
+    fn test_irq_enable() {
+        let mut nvic = NVIC::default();
+
+        nvic.irq_enable(Interrupt::EXTI0);
+        nvic.irq_enable(Interrupt::UART8);
+        assert_eq!(nvic.iser[0], 1 << 6);
+        assert_eq!(nvic.iser[2], 1 << (83 - 64));
+        assert!(nvic.irq_is_enabled(Interrupt::UART8));
+        assert!(!nvic.irq_is_enabled(Interrupt::UART7));
+    }
+
+    #[test]
+    fn test_irq_disable_single_bit() {
+        let mut nvic = NVIC::default();
+
+        // ICER reads back the enabled interrupts of its word
+        nvic.icer[1] = 0xffff_ffff;
+        nvic.irq_disable(Interrupt::USART1);
+        assert_eq!(nvic.icer[1], 1 << (37 - 32));
+
+        nvic.icpr[1] = 0xffff_ffff;
+        nvic.irq_clear_pending(Interrupt::USART2);
+        assert_eq!(nvic.icpr[1], 1 << (38 - 32));
+    }
+
+    #[test]
+    fn test_encode_priority() {
+        // all bits are group priority
+        assert_eq!(encode_priority(PriorityGrouping::Group7Sub1, 0xa, 0x1), 0xa);
+        assert_eq!(encode_priority(PriorityGrouping::Group4Sub4, 0xf, 0x3), 0xf);
+
+        // 2 bits each
+        assert_eq!(encode_priority(PriorityGrouping::Group2Sub6, 0x2, 0x1), 0b10_01);
+        assert_eq!(encode_priority(PriorityGrouping::Group2Sub6, 0x7, 0x7), 0b11_11);
+
+        // 1 bit of group priority
+        assert_eq!(encode_priority(PriorityGrouping::Group1Sub7, 0x1, 0x5), 0b1_101);
+
+        // all bits are subpriority
+        assert_eq!(encode_priority(PriorityGrouping::Group0Sub8, 0x3, 0x9), 0x9);
+    }
+
+    #[test]
+    fn test_decode_priority() {
+        assert_eq!(decode_priority(PriorityGrouping::Group4Sub4, 0xa), (0xa, 0));
+        assert_eq!(decode_priority(PriorityGrouping::Group3Sub5, 0b101_1), (0b101, 0b1));
+        assert_eq!(decode_priority(PriorityGrouping::Group2Sub6, 0b10_01), (0b10, 0b01));
+        assert_eq!(decode_priority(PriorityGrouping::Group0Sub8, 0x9), (0, 0x9));
+
+        for grouping in [
+            PriorityGrouping::Group7Sub1,
+            PriorityGrouping::Group4Sub4,
+            PriorityGrouping::Group3Sub5,
+            PriorityGrouping::Group1Sub7,
+            PriorityGrouping::Group0Sub8,
+        ] {
+            for priority in 0..16 {
+                let (preempt, sub) = decode_priority(grouping, priority);
+                assert_eq!(encode_priority(grouping, preempt, sub), priority);
+            }
+        }
+    }
+}
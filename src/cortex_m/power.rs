@@ -0,0 +1,86 @@
+//! Core low-power modes entered through WFI/WFE.
+//!
+//! Whether deep sleep ends up as Stop or Standby is decided by the PWR
+//! peripheral, this module only drives the core side (SCB::SCR).
+
+use super::{ asm, Peripherals };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SleepMode {
+    /// Core clock stopped, peripherals keep running
+    Sleep,
+
+    /// Core and most clocks stopped, SCR SLEEPDEEP set
+    DeepSleep,
+}
+
+/// Selects the mode entered by the next WFI/WFE
+pub fn set_sleep_mode(mode: SleepMode) {
+    let mut scb = Peripherals::scb();
+
+    match mode {
+        SleepMode::Sleep => scb.disable_sleep_deep(),
+        SleepMode::DeepSleep => scb.enable_sleep_deep(),
+    }
+}
+
+pub fn get_sleep_mode() -> SleepMode {
+    if Peripherals::scb().sleep_deep_is_enabled() { SleepMode::DeepSleep } else { SleepMode::Sleep }
+}
+
+/// With `enabled` set the core goes back to sleep as soon as the last
+/// interrupt handler returns, so an interrupt driven application never runs
+/// thread mode code again after the first WFI
+pub fn set_sleep_on_exit(enabled: bool) {
+    let mut scb = Peripherals::scb();
+
+    if enabled {
+        scb.enable_sleep_on_exit();
+    } else {
+        scb.disable_sleep_on_exit();
+    }
+}
+
+/// With `enabled` set any interrupt becoming pending wakes up WFE, even if it
+/// is disabled in the NVIC
+pub fn set_send_event_on_pending(enabled: bool) {
+    let mut scb = Peripherals::scb();
+
+    if enabled {
+        scb.enable_send_event_on_pending();
+    } else {
+        scb.disable_send_event_on_pending();
+    }
+}
+
+/// Sleeps until an interrupt is taken
+#[inline]
+pub fn wait_for_interrupt() {
+    asm::dsb();
+    asm::wfi();
+}
+
+/// Sleeps until an event arrives, e.g. from an EXTI line configured with one
+/// of the `InterruptType::*Event` variants, or `send_event`.
+///
+/// Returns immediately if the event register was already set.
+#[inline]
+pub fn wait_for_event() {
+    asm::dsb();
+    asm::wfe();
+}
+
+/// Sets the event register of the core
+#[inline]
+pub fn send_event() {
+    asm::sev();
+}
+
+/// Enters `mode` with WFI and restores regular sleep on wake-up
+pub fn sleep(mode: SleepMode) {
+    set_sleep_mode(mode);
+
+    wait_for_interrupt();
+
+    set_sleep_mode(SleepMode::Sleep);
+}
@@ -0,0 +1,311 @@
+//! Minimal runtime: reset handler, vector table and `.data`/`.bss` setup.
+//!
+//! Enabled with the `rt` feature. The build script puts `link.x` in the linker
+//! search path, the application provides a `memory.x` describing the flash,
+//! SRAM and CCM RAM of its part (see `examples/memory.x`), links with
+//! `-C link-arg=-Tlink.x` and names its entry point with [`entry!`](crate::entry).
+//!
+//! Every exception and interrupt handler is looked up by symbol name
+//! (`__nmi_handler`, `__systick_handler`, `__irqNN_handler`, ...). Handlers
+//! nobody defines fall back to `DefaultHandler`, which can be overridden
+//! too and otherwise spins forever.
+
+use super::asm;
+
+/// Vector table entry
+pub union Vector {
+    handler: unsafe extern "C" fn(),
+    reserved: usize,
+}
+
+extern "C" {
+    fn __reset_handler() -> !;
+
+    fn __nmi_handler();
+    fn __hard_fault_handler();
+    fn __mem_manage_handler();
+    fn __bus_fault_handler();
+    fn __usage_fault_handler();
+    fn __svcall_handler();
+    fn __debug_monitor_handler();
+    fn __pendsv_handler();
+    fn __systick_handler();
+
+    fn __irq0_handler();
+    fn __irq1_handler();
+    fn __irq2_handler();
+    fn __irq3_handler();
+    fn __irq4_handler();
+    fn __irq5_handler();
+    fn __irq6_handler();
+    fn __irq7_handler();
+    fn __irq8_handler();
+    fn __irq9_handler();
+    fn __irq10_handler();
+    fn __irq11_handler();
+    fn __irq12_handler();
+    fn __irq13_handler();
+    fn __irq14_handler();
+    fn __irq15_handler();
+    fn __irq16_handler();
+    fn __irq17_handler();
+    fn __irq18_handler();
+    fn __irq19_handler();
+    fn __irq20_handler();
+    fn __irq21_handler();
+    fn __irq22_handler();
+    fn __irq23_handler();
+    fn __irq24_handler();
+    fn __irq25_handler();
+    fn __irq26_handler();
+    fn __irq27_handler();
+    fn __irq28_handler();
+    fn __irq29_handler();
+    fn __irq30_handler();
+    fn __irq31_handler();
+    fn __irq32_handler();
+    fn __irq33_handler();
+    fn __irq34_handler();
+    fn __irq35_handler();
+    fn __irq36_handler();
+    fn __irq37_handler();
+    fn __irq38_handler();
+    fn __irq39_handler();
+    fn __irq40_handler();
+    fn __irq41_handler();
+    fn __irq42_handler();
+    fn __irq43_handler();
+    fn __irq44_handler();
+    fn __irq45_handler();
+    fn __irq46_handler();
+    fn __irq47_handler();
+    fn __irq48_handler();
+    fn __irq49_handler();
+    fn __irq50_handler();
+    fn __irq51_handler();
+    fn __irq52_handler();
+    fn __irq53_handler();
+    fn __irq54_handler();
+    fn __irq55_handler();
+    fn __irq56_handler();
+    fn __irq57_handler();
+    fn __irq58_handler();
+    fn __irq59_handler();
+    fn __irq60_handler();
+    fn __irq61_handler();
+    fn __irq62_handler();
+    fn __irq63_handler();
+    fn __irq64_handler();
+    fn __irq65_handler();
+    fn __irq66_handler();
+    fn __irq67_handler();
+    fn __irq68_handler();
+    fn __irq69_handler();
+    fn __irq70_handler();
+    fn __irq71_handler();
+    fn __irq72_handler();
+    fn __irq73_handler();
+    fn __irq74_handler();
+    fn __irq75_handler();
+    fn __irq76_handler();
+    fn __irq77_handler();
+    fn __irq78_handler();
+    fn __irq79_handler();
+    fn __irq80_handler();
+    fn __irq81_handler();
+    fn __irq82_handler();
+    fn __irq83_handler();
+    fn __irq84_handler();
+    fn __irq85_handler();
+    fn __irq86_handler();
+    fn __irq87_handler();
+    fn __irq88_handler();
+    fn __irq89_handler();
+    fn __irq90_handler();
+}
+
+#[doc(hidden)]
+#[link_section = ".vector_table.reset_vector"]
+#[no_mangle]
+pub static __RESET_VECTOR: unsafe extern "C" fn() -> ! = __reset_handler;
+
+#[doc(hidden)]
+#[link_section = ".vector_table.exceptions"]
+#[no_mangle]
+pub static __EXCEPTIONS: [Vector; 14] = [
+    Vector { handler: __nmi_handler },
+    Vector { handler: __hard_fault_handler },
+    Vector { handler: __mem_manage_handler },
+    Vector { handler: __bus_fault_handler },
+    Vector { handler: __usage_fault_handler },
+    Vector { reserved: 0 },
+    Vector { reserved: 0 },
+    Vector { reserved: 0 },
+    Vector { reserved: 0 },
+    Vector { handler: __svcall_handler },
+    Vector { handler: __debug_monitor_handler },
+    Vector { reserved: 0 },
+    Vector { handler: __pendsv_handler },
+    Vector { handler: __systick_handler },
+];
+
+#[doc(hidden)]
+#[link_section = ".vector_table.interrupts"]
+#[no_mangle]
+pub static __INTERRUPTS: [Vector; 91] = [
+    Vector { handler: __irq0_handler }, // WWDG
+    Vector { handler: __irq1_handler }, // PVD
+    Vector { handler: __irq2_handler }, // TAMP_STAMP
+    Vector { handler: __irq3_handler }, // RTC_WKUP
+    Vector { handler: __irq4_handler }, // FLASH
+    Vector { handler: __irq5_handler }, // RCC
+    Vector { handler: __irq6_handler }, // EXTI0
+    Vector { handler: __irq7_handler }, // EXTI1
+    Vector { handler: __irq8_handler }, // EXTI2
+    Vector { handler: __irq9_handler }, // EXTI3
+    Vector { handler: __irq10_handler }, // EXTI4
+    Vector { handler: __irq11_handler }, // DMA1_Stream0
+    Vector { handler: __irq12_handler }, // DMA1_Stream1
+    Vector { handler: __irq13_handler }, // DMA1_Stream2
+    Vector { handler: __irq14_handler }, // DMA1_Stream3
+    Vector { handler: __irq15_handler }, // DMA1_Stream4
+    Vector { handler: __irq16_handler }, // DMA1_Stream5
+    Vector { handler: __irq17_handler }, // DMA1_Stream6
+    Vector { handler: __irq18_handler }, // ADC
+    Vector { handler: __irq19_handler }, // CAN1_TX
+    Vector { handler: __irq20_handler }, // CAN1_RX0
+    Vector { handler: __irq21_handler }, // CAN1_RX1
+    Vector { handler: __irq22_handler }, // CAN1_SCE
+    Vector { handler: __irq23_handler }, // EXTI9_5
+    Vector { handler: __irq24_handler }, // TIM1_BRK_TIM9
+    Vector { handler: __irq25_handler }, // TIM1_UP_TIM10
+    Vector { handler: __irq26_handler }, // TIM1_TRG_COM_TIM11
+    Vector { handler: __irq27_handler }, // TIM1_CC
+    Vector { handler: __irq28_handler }, // TIM2
+    Vector { handler: __irq29_handler }, // TIM3
+    Vector { handler: __irq30_handler }, // TIM4
+    Vector { handler: __irq31_handler }, // I2C1_EV
+    Vector { handler: __irq32_handler }, // I2C1_ER
+    Vector { handler: __irq33_handler }, // I2C2_EV
+    Vector { handler: __irq34_handler }, // I2C2_ER
+    Vector { handler: __irq35_handler }, // SPI1
+    Vector { handler: __irq36_handler }, // SPI2
+    Vector { handler: __irq37_handler }, // USART1
+    Vector { handler: __irq38_handler }, // USART2
+    Vector { handler: __irq39_handler }, // USART3
+    Vector { handler: __irq40_handler }, // EXTI15_10
+    Vector { handler: __irq41_handler }, // RTC_Alarm
+    Vector { handler: __irq42_handler }, // OTG_FS_WKUP
+    Vector { handler: __irq43_handler }, // TIM8_BRK_TIM12
+    Vector { handler: __irq44_handler }, // TIM8_UP_TIM13
+    Vector { handler: __irq45_handler }, // TIM8_TRG_COM_TIM14
+    Vector { handler: __irq46_handler }, // TIM8_CC
+    Vector { handler: __irq47_handler }, // DMA1_Stream7
+    Vector { handler: __irq48_handler }, // FMC
+    Vector { handler: __irq49_handler }, // SDIO
+    Vector { handler: __irq50_handler }, // TIM5
+    Vector { handler: __irq51_handler }, // SPI3
+    Vector { handler: __irq52_handler }, // UART4
+    Vector { handler: __irq53_handler }, // UART5
+    Vector { handler: __irq54_handler }, // TIM6_DAC
+    Vector { handler: __irq55_handler }, // TIM7
+    Vector { handler: __irq56_handler }, // DMA2_Stream0
+    Vector { handler: __irq57_handler }, // DMA2_Stream1
+    Vector { handler: __irq58_handler }, // DMA2_Stream2
+    Vector { handler: __irq59_handler }, // DMA2_Stream3
+    Vector { handler: __irq60_handler }, // DMA2_Stream4
+    Vector { handler: __irq61_handler }, // ETH
+    Vector { handler: __irq62_handler }, // ETH_WKUP
+    Vector { handler: __irq63_handler }, // CAN2_TX
+    Vector { handler: __irq64_handler }, // CAN2_RX0
+    Vector { handler: __irq65_handler }, // CAN2_RX1
+    Vector { handler: __irq66_handler }, // CAN2_SCE
+    Vector { handler: __irq67_handler }, // OTG_FS
+    Vector { handler: __irq68_handler }, // DMA2_Stream5
+    Vector { handler: __irq69_handler }, // DMA2_Stream6
+    Vector { handler: __irq70_handler }, // DMA2_Stream7
+    Vector { handler: __irq71_handler }, // USART6
+    Vector { handler: __irq72_handler }, // I2C3_EV
+    Vector { handler: __irq73_handler }, // I2C3_ER
+    Vector { handler: __irq74_handler }, // OTG_HS_EP1_OUT
+    Vector { handler: __irq75_handler }, // OTG_HS_EP1_IN
+    Vector { handler: __irq76_handler }, // OTG_HS_WKUP
+    Vector { handler: __irq77_handler }, // OTG_HS
+    Vector { handler: __irq78_handler }, // DCMI
+    Vector { handler: __irq79_handler }, // CRYP
+    Vector { handler: __irq80_handler }, // HASH_RNG
+    Vector { handler: __irq81_handler }, // FPU
+    Vector { handler: __irq82_handler }, // UART7
+    Vector { handler: __irq83_handler }, // UART8
+    Vector { handler: __irq84_handler }, // SPI4
+    Vector { handler: __irq85_handler }, // SPI5
+    Vector { handler: __irq86_handler }, // SPI6
+    Vector { handler: __irq87_handler }, // SAI1
+    Vector { handler: __irq88_handler }, // LTDC
+    Vector { handler: __irq89_handler }, // LTDC_ER
+    Vector { handler: __irq90_handler }, // DMA2D
+];
+
+#[no_mangle]
+unsafe extern "C" fn __default_handler() {
+    loop {
+        asm::nop();
+    }
+}
+
+// Runs before anything else: zeroes .bss, copies .data from flash, grants
+// access to the FPU (used by hard-float code right away) and calls the
+// function registered with `entry!`.
+core::arch::global_asm!(
+    ".section .text.__reset_handler,\"ax\",%progbits",
+    ".global __reset_handler",
+    ".type __reset_handler,%function",
+    ".thumb_func",
+    "__reset_handler:",
+    "    ldr r0, =__sbss",
+    "    ldr r1, =__ebss",
+    "    movs r2, #0",
+    "0:  cmp r0, r1",
+    "    bhs 1f",
+    "    str r2, [r0], #4",
+    "    b 0b",
+    "1:  ldr r0, =__sdata",
+    "    ldr r1, =__edata",
+    "    ldr r2, =__sidata",
+    "2:  cmp r0, r1",
+    "    bhs 3f",
+    "    ldr r3, [r2], #4",
+    "    str r3, [r0], #4",
+    "    b 2b",
+    "3:  ldr r0, =0xe000ed88",
+    "    ldr r1, [r0]",
+    "    orr r1, r1, #0x00f00000",
+    "    str r1, [r0]",
+    "    dsb",
+    "    isb",
+    "    bl __rt_main",
+    "    udf #0",
+    ".ltorg",
+);
+
+/// Declares the application entry point, called by the reset handler once
+/// memory is initialised.
+///
+/// ```ignore
+/// rusty_peripheral::entry!(main);
+///
+/// fn main() -> ! {
+///     loop {}
+/// }
+/// ```
+#[macro_export]
+macro_rules! entry {
+    ($path:path) => {
+        #[doc(hidden)]
+        #[export_name = "__rt_main"]
+        pub unsafe extern "C" fn __rt_main() -> ! {
+            let f: fn() -> ! = $path;
+            f()
+        }
+    };
+}
@@ -1,7 +1,13 @@
 #![allow(dead_code)]
 
+use core::fmt;
+
+use super::{ asm, NVIC_PRIO_BITS };
+
 use self::register::*;
 
+pub use self::register::{ Endianness, PriorityGrouping };
+
 mod register;
 
 pub struct RegisterBlock {
@@ -10,10 +16,387 @@ pub struct RegisterBlock {
 
     /// Interrupt Control and State Register
     icsr: InterruptControlAndStateRegister,
+
+    /// Vector Table Offset Register
+    vtor: VectorTableOffsetRegister,
+
+    /// Application Interrupt and Reset Control Register
+    aircr: ApplicationInterruptAndResetControlRegister,
+
+    /// System Control Register
+    scr: SystemControlRegister,
+
+    /// Configuration and Control Register
+    ccr: ConfigurationAndControlRegister,
+
+    /// System Handler Priority Register 1
+    shpr1: SystemHandlerPriorityRegister1,
+
+    /// System Handler Priority Register 2
+    shpr2: SystemHandlerPriorityRegister2,
+
+    /// System Handler Priority Register 3
+    shpr3: SystemHandlerPriorityRegister3,
+
+    /// System Handler Control and State Register
+    shcsr: SystemHandlerControlAndStateRegister,
+
+    /// Configurable Fault Status Register
+    cfsr: ConfigurableFaultStatusRegister,
+
+    /// HardFault Status Register
+    hfsr: HardFaultStatusRegister,
+
+    /// Debug Fault Status Register
+    __reserved0: u32,
+
+    /// MemManage Fault Address Register
+    mmfar: MemoryManagementFaultAddressRegister,
+
+    /// BusFault Address Register
+    bfar: BusFaultAddressRegister,
+}
+
+/// Key that has to accompany every write to AIRCR
+const AIRCR_VECTKEY: u32 = 0x05fa << 16;
+
+/// Position of the PRIGROUP field in AIRCR
+const AIRCR_PRIGROUP_POS: u32 = 8;
+
+/// Position of the SYSRESETREQ bit in AIRCR
+const AIRCR_SYSRESETREQ_POS: u32 = 2;
+
+/// Processor identification decoded from the CPUID Base Register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuId {
+    /// Implementer code, `0x41` for ARM
+    pub implementer: u8,
+
+    /// Major revision, the `r` in `rNpM`
+    pub variant: u8,
+
+    /// Part number, `0xc24` for the Cortex-M4
+    pub part_number: u16,
+
+    /// Minor revision, the `p` in `rNpM`
+    pub revision: u8,
+}
+
+impl CpuId {
+    pub const IMPLEMENTER_ARM: u8 = 0x41;
+    pub const PART_CORTEX_M4: u16 = 0xc24;
+
+    pub fn from_bits(cpuid: u32) -> Self {
+        Self {
+            implementer: (cpuid >> 24) as u8,
+            variant: ((cpuid >> 20) & 0xf) as u8,
+            part_number: ((cpuid >> 4) & 0xfff) as u16,
+            revision: (cpuid & 0xf) as u8,
+        }
+    }
+
+    pub fn is_cortex_m4(&self) -> bool {
+        self.implementer == Self::IMPLEMENTER_ARM && self.part_number == Self::PART_CORTEX_M4
+    }
+}
+
+impl fmt::Display for CpuId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match (self.implementer, self.part_number) {
+            (Self::IMPLEMENTER_ARM, Self::PART_CORTEX_M4) => "Cortex-M4",
+            _ => "unknown core",
+        };
+
+        f.write_fmt(
+            format_args!(
+                "{} r{}p{} (implementer {:#04x}, part {:#05x})",
+                name,
+                self.variant,
+                self.revision,
+                self.implementer,
+                self.part_number
+            )
+        )
+    }
 }
 
 impl RegisterBlock {
+    pub fn cpuid(&self) -> CpuId {
+        CpuId::from_bits(self.cpuid.get())
+    }
+
     pub fn get_active_interrupt_number(&self) -> i16 {
         self.icsr.get_active_vector() as i16 - 16
     }
+
+    /// Requests a system reset and waits for it to happen
+    pub fn system_reset(&mut self) -> ! {
+        asm::dsb();
+
+        let prigroup = self.aircr.get() & (0b111 << AIRCR_PRIGROUP_POS);
+        self.aircr.set(AIRCR_VECTKEY | prigroup | (1 << AIRCR_SYSRESETREQ_POS));
+
+        asm::dsb();
+
+        loop {
+            asm::nop();
+        }
+    }
+
+    #[inline]
+    pub fn get_priority_grouping(&self) -> PriorityGrouping {
+        self.aircr.get_priority_grouping()
+    }
+
+    /// Sets the split between group (pre-emption) priority and subpriority
+    pub fn set_priority_grouping(&mut self, grouping: PriorityGrouping) {
+        let aircr = self.aircr.get() & !((0xffff << 16) | (0b111 << AIRCR_PRIGROUP_POS));
+        let prigroup = (grouping as u32) << AIRCR_PRIGROUP_POS;
+
+        self.aircr.set(AIRCR_VECTKEY | prigroup | aircr);
+    }
+
+    #[inline]
+    pub fn get_endianness(&self) -> Endianness {
+        self.aircr.get_endianness()
+    }
+
+    #[inline]
+    pub fn vector_table(&self) -> u32 {
+        self.vtor.get_table_offset() << 7
+    }
+
+    /// Moves the vector table to `addr`.
+    ///
+    /// The table holds 107 entries so `addr` has to be aligned to 512 bytes.
+    pub fn relocate_vector_table(&mut self, addr: u32) {
+        assert!(addr & 0x1ff == 0, "vector table must be aligned to 512 bytes");
+
+        self.vtor.set_table_offset(addr >> 7);
+
+        asm::dsb();
+        asm::isb();
+    }
+
+    /// Enters sleep again on return from the last active handler
+    #[inline]
+    pub fn enable_sleep_on_exit(&mut self) {
+        self.scr.enable_sleep_on_exit()
+    }
+
+    #[inline]
+    pub fn disable_sleep_on_exit(&mut self) {
+        self.scr.disable_sleep_on_exit()
+    }
+
+    #[inline]
+    pub fn sleep_on_exit_is_enabled(&self) -> bool {
+        self.scr.sleep_on_exit_is_enabled()
+    }
+
+    /// Makes WFI/WFE enter deep sleep (Stop or Standby on the STM32F4)
+    #[inline]
+    pub fn enable_sleep_deep(&mut self) {
+        self.scr.enable_sleep_deep()
+    }
+
+    #[inline]
+    pub fn disable_sleep_deep(&mut self) {
+        self.scr.disable_sleep_deep()
+    }
+
+    #[inline]
+    pub fn sleep_deep_is_enabled(&self) -> bool {
+        self.scr.sleep_deep_is_enabled()
+    }
+
+    /// Makes a newly pending interrupt wake up WFE even if it is disabled
+    #[inline]
+    pub fn enable_send_event_on_pending(&mut self) {
+        self.scr.enable_send_event_on_pending()
+    }
+
+    #[inline]
+    pub fn disable_send_event_on_pending(&mut self) {
+        self.scr.disable_send_event_on_pending()
+    }
+
+    #[inline]
+    pub fn send_event_on_pending_is_enabled(&self) -> bool {
+        self.scr.send_event_on_pending_is_enabled()
+    }
+
+    #[inline]
+    pub fn svcall_get_priority(&self) -> u8 {
+        self.shpr2.svcall_get_priority() >> (8 - NVIC_PRIO_BITS)
+    }
+
+    #[inline]
+    pub fn svcall_set_priority(&mut self, priority: u8) {
+        self.shpr2.svcall_set_priority(shpr_value(priority));
+    }
+
+    #[inline]
+    pub fn pendsv_get_priority(&self) -> u8 {
+        self.shpr3.pendsv_get_priority() >> (8 - NVIC_PRIO_BITS)
+    }
+
+    #[inline]
+    pub fn pendsv_set_priority(&mut self, priority: u8) {
+        self.shpr3.pendsv_set_priority(shpr_value(priority));
+    }
+
+    #[inline]
+    pub fn systick_get_priority(&self) -> u8 {
+        self.shpr3.systick_get_priority() >> (8 - NVIC_PRIO_BITS)
+    }
+
+    #[inline]
+    pub fn systick_set_priority(&mut self, priority: u8) {
+        self.shpr3.systick_set_priority(shpr_value(priority));
+    }
+
+    #[inline]
+    pub fn memory_management_fault_set_priority(&mut self, priority: u8) {
+        self.shpr1.memory_management_fault_set_priority(shpr_value(priority));
+    }
+
+    #[inline]
+    pub fn bus_fault_set_priority(&mut self, priority: u8) {
+        self.shpr1.bus_fault_set_priority(shpr_value(priority));
+    }
+
+    #[inline]
+    pub fn usage_fault_set_priority(&mut self, priority: u8) {
+        self.shpr1.usage_fault_set_priority(shpr_value(priority));
+    }
+
+    #[inline]
+    pub fn memory_management_fault_enable(&mut self) {
+        self.shcsr.memory_management_fault_enable()
+    }
+
+    #[inline]
+    pub fn memory_management_fault_disable(&mut self) {
+        self.shcsr.memory_management_fault_disable()
+    }
+
+    #[inline]
+    pub fn memory_management_fault_is_enabled(&self) -> bool {
+        self.shcsr.memory_management_fault_is_enabled()
+    }
+
+    #[inline]
+    pub fn bus_fault_enable(&mut self) {
+        self.shcsr.bus_fault_enable()
+    }
+
+    #[inline]
+    pub fn bus_fault_disable(&mut self) {
+        self.shcsr.bus_fault_disable()
+    }
+
+    #[inline]
+    pub fn bus_fault_is_enabled(&self) -> bool {
+        self.shcsr.bus_fault_is_enabled()
+    }
+
+    #[inline]
+    pub fn usage_fault_enable(&mut self) {
+        self.shcsr.usage_fault_enable()
+    }
+
+    #[inline]
+    pub fn usage_fault_disable(&mut self) {
+        self.shcsr.usage_fault_disable()
+    }
+
+    #[inline]
+    pub fn usage_fault_is_enabled(&self) -> bool {
+        self.shcsr.usage_fault_is_enabled()
+    }
+
+    #[inline]
+    pub fn enable_divide_by_zero_trap(&mut self) {
+        self.ccr.enable_divide_by_zero_trap()
+    }
+
+    #[inline]
+    pub fn enable_unaligned_trap(&mut self) {
+        self.ccr.enable_unaligned_trap()
+    }
+
+    /// Raw Configurable Fault Status Register value
+    #[inline]
+    pub fn fault_status(&self) -> u32 {
+        self.cfsr.get()
+    }
+
+    /// Raw HardFault Status Register value
+    #[inline]
+    pub fn hard_fault_status(&self) -> u32 {
+        self.hfsr.get()
+    }
+
+    #[inline]
+    pub fn memory_management_fault_address(&self) -> u32 {
+        self.mmfar.get_address()
+    }
+
+    #[inline]
+    pub fn bus_fault_address(&self) -> u32 {
+        self.bfar.get_address()
+    }
+
+    /// Clears all sticky fault status bits
+    pub fn clear_fault_status(&mut self) {
+        // both registers are write-one-to-clear
+        self.cfsr.set(self.cfsr.get());
+        self.hfsr.set(self.hfsr.get());
+    }
+}
+
+/// SHPR byte for a system handler `priority`, which sits in the upper
+/// `NVIC_PRIO_BITS` bits. Panics if it does not fit instead of truncating it.
+#[inline]
+fn shpr_value(priority: u8) -> u8 {
+    assert!(priority < 1 << NVIC_PRIO_BITS, "system handler priority out of range");
+
+    priority << (8 - NVIC_PRIO_BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shpr_value() {
+        assert_eq!(shpr_value(0), 0x00);
+        assert_eq!(shpr_value(3), 0x30);
+        assert_eq!(shpr_value(15), 0xf0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_shpr_value_out_of_range() {
+        shpr_value(16);
+    }
+
+    #[test]
+    fn test_cpuid_decoding() {
+        // Cortex-M4 r0p1 found in the STM32F42x
+        let cpuid = CpuId::from_bits(0x410f_c241);
+        assert_eq!(cpuid.implementer, CpuId::IMPLEMENTER_ARM);
+        assert_eq!(cpuid.variant, 0);
+        assert_eq!(cpuid.part_number, CpuId::PART_CORTEX_M4);
+        assert_eq!(cpuid.revision, 1);
+        assert!(cpuid.is_cortex_m4());
+
+        // Cortex-M7 r1p2
+        let cpuid = CpuId::from_bits(0x411f_c272);
+        assert_eq!(cpuid.variant, 1);
+        assert_eq!(cpuid.part_number, 0xc27);
+        assert_eq!(cpuid.revision, 2);
+        assert!(!cpuid.is_cortex_m4());
+    }
 }
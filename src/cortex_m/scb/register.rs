@@ -1,4 +1,4 @@
-use register::register;
+use register::{ field::derive::RegisterField, register };
 
 #[register(u32)]
 pub(super) struct CPUIDBaseRegister {
@@ -58,4 +58,393 @@ pub(super) struct InterruptControlAndStateRegister {
 
     #[bits(1, rw, get = nmi_exception_is_pending, set = nmi_exception_set_pending)]
     pub(super) NMIPENDSET: bool,
-}
\ No newline at end of file
+}
+
+#[register(u32)]
+pub(super) struct VectorTableOffsetRegister {
+    #[bits(7)]
+    __: u32,
+
+    #[bits(25, rw, get = get_table_offset, set = set_table_offset)]
+    pub(super) TBLOFF: u32,
+}
+
+#[register(u32)]
+pub(super) struct ApplicationInterruptAndResetControlRegister {
+    #[bits(1, w, set = request_local_reset)]
+    pub(super) VECTRESET: bool,
+
+    #[bits(1, w, set = clear_active_state)]
+    pub(super) VECTCLRACTIVE: bool,
+
+    #[bits(1, w, set = request_system_reset)]
+    pub(super) SYSRESETREQ: bool,
+
+    #[bits(5)]
+    __: u32,
+
+    #[bits(3, r, get = get_priority_grouping)]
+    pub(super) PRIGROUP: PriorityGrouping,
+
+    #[bits(4)]
+    __: u32,
+
+    #[bits(1, r, get = get_endianness)]
+    pub(super) ENDIANNESS: Endianness,
+
+    #[bits(16, r, get = get_vector_key)]
+    pub(super) VECTKEY: u16,
+}
+
+#[register(u32)]
+pub(super) struct SystemControlRegister {
+    #[bits(1)]
+    __: u32,
+
+    #[bits(
+        1,
+        rwc,
+        get = sleep_on_exit_is_enabled,
+        set = enable_sleep_on_exit,
+        clear = disable_sleep_on_exit
+    )]
+    pub(super) SLEEPONEXIT: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = sleep_deep_is_enabled,
+        set = enable_sleep_deep,
+        clear = disable_sleep_deep
+    )]
+    pub(super) SLEEPDEEP: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(
+        1,
+        rwc,
+        get = send_event_on_pending_is_enabled,
+        set = enable_send_event_on_pending,
+        clear = disable_send_event_on_pending
+    )]
+    pub(super) SEVONPEND: bool,
+
+    #[bits(27)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct ConfigurationAndControlRegister {
+    #[bits(
+        1,
+        rwc,
+        get = non_base_thread_is_enabled,
+        set = enable_non_base_thread,
+        clear = disable_non_base_thread
+    )]
+    pub(super) NONBASETHRDENA: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = user_stir_access_is_enabled,
+        set = enable_user_stir_access,
+        clear = disable_user_stir_access
+    )]
+    pub(super) USERSETMPEND: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(
+        1,
+        rwc,
+        get = unaligned_trap_is_enabled,
+        set = enable_unaligned_trap,
+        clear = disable_unaligned_trap
+    )]
+    pub(super) UNALIGN_TRP: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = divide_by_zero_trap_is_enabled,
+        set = enable_divide_by_zero_trap,
+        clear = disable_divide_by_zero_trap
+    )]
+    pub(super) DIV_0_TRP: bool,
+
+    #[bits(3)]
+    __: u32,
+
+    #[bits(
+        1,
+        rwc,
+        get = bus_fault_ignore_is_enabled,
+        set = enable_bus_fault_ignore,
+        clear = disable_bus_fault_ignore
+    )]
+    pub(super) BFHFNMIGN: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = stack_alignment_is_enabled,
+        set = enable_stack_alignment,
+        clear = disable_stack_alignment
+    )]
+    pub(super) STKALIGN: bool,
+
+    #[bits(22)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct SystemHandlerPriorityRegister1 {
+    #[bits(
+        8,
+        rw,
+        get = memory_management_fault_get_priority,
+        set = memory_management_fault_set_priority
+    )]
+    pub(super) PRI_4: u8,
+
+    #[bits(8, rw, get = bus_fault_get_priority, set = bus_fault_set_priority)]
+    pub(super) PRI_5: u8,
+
+    #[bits(8, rw, get = usage_fault_get_priority, set = usage_fault_set_priority)]
+    pub(super) PRI_6: u8,
+
+    #[bits(8)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct SystemHandlerPriorityRegister2 {
+    #[bits(24)]
+    __: u32,
+
+    #[bits(8, rw, get = svcall_get_priority, set = svcall_set_priority)]
+    pub(super) PRI_11: u8,
+}
+
+#[register(u32)]
+pub(super) struct SystemHandlerPriorityRegister3 {
+    #[bits(16)]
+    __: u32,
+
+    #[bits(8, rw, get = pendsv_get_priority, set = pendsv_set_priority)]
+    pub(super) PRI_14: u8,
+
+    #[bits(8, rw, get = systick_get_priority, set = systick_set_priority)]
+    pub(super) PRI_15: u8,
+}
+
+#[register(u32)]
+pub(super) struct SystemHandlerControlAndStateRegister {
+    #[bits(1, r, get = memory_management_fault_is_active)]
+    pub(super) MEMFAULTACT: bool,
+
+    #[bits(1, r, get = bus_fault_is_active)]
+    pub(super) BUSFAULTACT: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, r, get = usage_fault_is_active)]
+    pub(super) USGFAULTACT: bool,
+
+    #[bits(3)]
+    __: u32,
+
+    #[bits(1, r, get = svcall_is_active)]
+    pub(super) SVCALLACT: bool,
+
+    #[bits(1, r, get = debug_monitor_is_active)]
+    pub(super) MONITORACT: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, r, get = pendsv_is_active)]
+    pub(super) PENDSVACT: bool,
+
+    #[bits(1, r, get = systick_is_active)]
+    pub(super) SYSTICKACT: bool,
+
+    #[bits(1, r, get = usage_fault_is_pending)]
+    pub(super) USGFAULTPENDED: bool,
+
+    #[bits(1, r, get = memory_management_fault_is_pending)]
+    pub(super) MEMFAULTPENDED: bool,
+
+    #[bits(1, r, get = bus_fault_is_pending)]
+    pub(super) BUSFAULTPENDED: bool,
+
+    #[bits(1, r, get = svcall_is_pending)]
+    pub(super) SVCALLPENDED: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = memory_management_fault_is_enabled,
+        set = memory_management_fault_enable,
+        clear = memory_management_fault_disable
+    )]
+    pub(super) MEMFAULTENA: bool,
+
+    #[bits(1, rwc, get = bus_fault_is_enabled, set = bus_fault_enable, clear = bus_fault_disable)]
+    pub(super) BUSFAULTENA: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = usage_fault_is_enabled,
+        set = usage_fault_enable,
+        clear = usage_fault_disable
+    )]
+    pub(super) USGFAULTENA: bool,
+
+    #[bits(13)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct ConfigurableFaultStatusRegister {
+    #[bits(1, r, get = is_instruction_access_violation)]
+    pub(super) IACCVIOL: bool,
+
+    #[bits(1, r, get = is_data_access_violation)]
+    pub(super) DACCVIOL: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, r, get = is_memory_management_unstacking_fault)]
+    pub(super) MUNSTKERR: bool,
+
+    #[bits(1, r, get = is_memory_management_stacking_fault)]
+    pub(super) MSTKERR: bool,
+
+    #[bits(1, r, get = is_memory_management_lazy_fp_fault)]
+    pub(super) MLSPERR: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, r, get = is_mmfar_valid)]
+    pub(super) MMARVALID: bool,
+
+    #[bits(1, r, get = is_instruction_bus_error)]
+    pub(super) IBUSERR: bool,
+
+    #[bits(1, r, get = is_precise_data_bus_error)]
+    pub(super) PRECISERR: bool,
+
+    #[bits(1, r, get = is_imprecise_data_bus_error)]
+    pub(super) IMPRECISERR: bool,
+
+    #[bits(1, r, get = is_bus_unstacking_fault)]
+    pub(super) UNSTKERR: bool,
+
+    #[bits(1, r, get = is_bus_stacking_fault)]
+    pub(super) STKERR: bool,
+
+    #[bits(1, r, get = is_bus_lazy_fp_fault)]
+    pub(super) LSPERR: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, r, get = is_bfar_valid)]
+    pub(super) BFARVALID: bool,
+
+    #[bits(1, r, get = is_undefined_instruction)]
+    pub(super) UNDEFINSTR: bool,
+
+    #[bits(1, r, get = is_invalid_state)]
+    pub(super) INVSTATE: bool,
+
+    #[bits(1, r, get = is_invalid_pc_load)]
+    pub(super) INVPC: bool,
+
+    #[bits(1, r, get = is_no_coprocessor)]
+    pub(super) NOCP: bool,
+
+    #[bits(4)]
+    __: u32,
+
+    #[bits(1, r, get = is_unaligned_access)]
+    pub(super) UNALIGNED: bool,
+
+    #[bits(1, r, get = is_divide_by_zero)]
+    pub(super) DIVBYZERO: bool,
+
+    #[bits(6)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct HardFaultStatusRegister {
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, r, get = is_vector_table_read_fault)]
+    pub(super) VECTTBL: bool,
+
+    #[bits(28)]
+    __: u32,
+
+    #[bits(1, r, get = is_forced)]
+    pub(super) FORCED: bool,
+
+    #[bits(1, r, get = is_debug_event)]
+    pub(super) DEBUGEVT: bool,
+}
+
+#[register(u32)]
+pub(super) struct MemoryManagementFaultAddressRegister {
+    #[bits(32, rw, get = get_address, set = set_address)]
+    pub(super) ADDRESS: u32,
+}
+
+#[register(u32)]
+pub(super) struct BusFaultAddressRegister {
+    #[bits(32, rw, get = get_address, set = set_address)]
+    pub(super) ADDRESS: u32,
+}
+
+#[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityGrouping {
+    /// 7 bits of group priority, 1 bit of subpriority
+    Group7Sub1 = 0b000,
+
+    /// 6 bits of group priority, 2 bits of subpriority
+    Group6Sub2 = 0b001,
+
+    /// 5 bits of group priority, 3 bits of subpriority
+    Group5Sub3 = 0b010,
+
+    /// 4 bits of group priority, 4 bits of subpriority
+    Group4Sub4 = 0b011,
+
+    /// 3 bits of group priority, 5 bits of subpriority
+    Group3Sub5 = 0b100,
+
+    /// 2 bits of group priority, 6 bits of subpriority
+    Group2Sub6 = 0b101,
+
+    /// 1 bit of group priority, 7 bits of subpriority
+    Group1Sub7 = 0b110,
+
+    /// No group priority, 8 bits of subpriority
+    Group0Sub8 = 0b111,
+}
+
+#[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    Little = 0b0,
+    Big = 0b1,
+}
@@ -0,0 +1,189 @@
+#![allow(dead_code)]
+
+use core::sync::atomic::{ AtomicU32, Ordering };
+
+use crate::rcc::rcc;
+
+use self::register::*;
+
+pub use self::register::ClockSource;
+
+mod register;
+
+/// Number of SysTick exceptions per second when configured by [`RegisterBlock::init`]
+pub const TICK_FREQ: u32 = 1_000;
+
+/// Milliseconds elapsed since the timer was started
+static TICKS: AtomicU32 = AtomicU32::new(0);
+
+pub struct RegisterBlock {
+    /// SysTick Control and Status Register
+    csr: ControlAndStatusRegister,
+
+    /// SysTick Reload Value Register
+    rvr: ReloadValueRegister,
+
+    /// SysTick Current Value Register
+    cvr: CurrentValueRegister,
+
+    /// SysTick Calibration Value Register
+    calib: CalibrationValueRegister,
+}
+
+impl RegisterBlock {
+    /// Largest value that fits into the 24-bit reload register
+    pub const MAX_RELOAD: u32 = 0x00ff_ffff;
+
+    /// Configures the timer for a millisecond tick and starts it.
+    ///
+    /// The reload value is computed from the current `HCLK` frequency, so this
+    /// has to be called again after the clock tree is reconfigured.
+    pub fn init(&mut self, source: ClockSource) {
+        self.disable();
+
+        self.csr.set_clock_source(source);
+        self.rvr.set_reload_value(reload_value(self.clock_freq(), TICK_FREQ));
+        self.cvr.clear_current_value(0);
+
+        self.csr.enable_tick_interrupt();
+        self.enable();
+    }
+
+    #[inline]
+    pub fn enable(&mut self) {
+        self.csr.enable_counter()
+    }
+
+    #[inline]
+    pub fn disable(&mut self) {
+        self.csr.disable_counter()
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.csr.counter_is_enabled()
+    }
+
+    #[inline]
+    pub fn clock_source(&self) -> ClockSource {
+        self.csr.get_clock_source()
+    }
+
+    /// Frequency the counter is decremented at
+    pub fn clock_freq(&self) -> u32 {
+        match self.clock_source() {
+            ClockSource::HCLKDividedBy8 => rcc().hclk_freq() / 8,
+            ClockSource::HCLK => rcc().hclk_freq(),
+        }
+    }
+
+    /// Milliseconds elapsed since the first [`RegisterBlock::init`], wraps
+    /// after ~49 days. Later calls keep counting from the current value.
+    ///
+    /// Counted by the SysTick handler of the `rt` feature, without it the
+    /// application has to call [`tick`] from its own handler.
+    #[inline]
+    pub fn ticks(&self) -> u32 {
+        TICKS.load(Ordering::Relaxed)
+    }
+
+    /// Busy-waits for `us` microseconds.
+    ///
+    /// The delay is measured on the counter itself, so it also works with the
+    /// SysTick exception masked. The counter has to be running.
+    pub fn delay_us(&self, us: u32) {
+        let reload = self.rvr.get_reload_value() + 1;
+        let mut remaining = (us as u64) * (self.clock_freq() as u64) / 1_000_000;
+        let mut last = self.cvr.get_current_value();
+
+        while remaining > 0 {
+            let now = self.cvr.get_current_value();
+
+            remaining = remaining.saturating_sub(elapsed_counts(last, now, reload) as u64);
+            last = now;
+        }
+    }
+
+    /// Busy-waits for `ms` milliseconds
+    pub fn delay_ms(&self, ms: u32) {
+        for _ in 0..ms {
+            self.delay_us(1_000);
+        }
+    }
+}
+
+/// Computes the reload value producing `tick_freq` exceptions per second.
+///
+/// Panics if the period does not fit in the 24-bit counter, or is shorter
+/// than two clock cycles: with a reload value of 0 the counter never wraps.
+fn reload_value(clock_freq: u32, tick_freq: u32) -> u32 {
+    assert!(tick_freq > 0 && clock_freq / tick_freq >= 2, "SysTick clock too slow for the tick");
+
+    let reload = clock_freq / tick_freq - 1;
+    assert!(reload <= RegisterBlock::MAX_RELOAD, "SysTick period does not fit in 24 bits");
+
+    reload
+}
+
+/// Counts elapsed between two reads of the down-counter, which wraps from 0
+/// back to `reload - 1`
+#[inline]
+fn elapsed_counts(last: u32, now: u32, reload: u32) -> u32 {
+    if now <= last { last - now } else { last + reload - now }
+}
+
+/// Advances the counter read by [`RegisterBlock::ticks`], to be called from
+/// the SysTick handler when the application or an RTOS owns it
+#[inline]
+pub fn tick() {
+    TICKS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// SysTick exception handler
+#[cfg(feature = "rt")]
+#[no_mangle]
+unsafe extern "C" fn __systick_handler() {
+    tick();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reload_value() {
+        assert_eq!(reload_value(180_000_000, 1_000), 179_999);
+        assert_eq!(reload_value(16_000_000 / 8, 1_000), 1_999);
+        assert_eq!(reload_value(2_000, 1_000), 1);
+        assert_eq!(reload_value(16_777_216, 1), RegisterBlock::MAX_RELOAD);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_reload_value_clock_too_slow() {
+        reload_value(999, 1_000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_reload_value_zero() {
+        reload_value(1_000, 1_000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_reload_value_period_too_long() {
+        reload_value(180_000_000, 10);
+    }
+
+    #[test]
+    fn test_elapsed_counts_wrap() {
+        // counting down without wrapping
+        assert_eq!(elapsed_counts(1_000, 400, 180_000), 600);
+        assert_eq!(elapsed_counts(400, 400, 180_000), 0);
+
+        // wrapped through 0 and reloaded with 179_999
+        assert_eq!(elapsed_counts(100, 179_900, 180_000), 200);
+        assert_eq!(elapsed_counts(0, 179_999, 180_000), 1);
+    }
+}
@@ -0,0 +1,70 @@
+use register::{ field::derive::RegisterField, register };
+
+#[register(u32)]
+pub(super) struct ControlAndStatusRegister {
+    #[bits(1, rwc, get = counter_is_enabled, set = enable_counter, clear = disable_counter)]
+    pub(super) ENABLE: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = tick_interrupt_is_enabled,
+        set = enable_tick_interrupt,
+        clear = disable_tick_interrupt
+    )]
+    pub(super) TICKINT: bool,
+
+    #[bits(1, rw, get = get_clock_source, set = set_clock_source)]
+    pub(super) CLKSOURCE: ClockSource,
+
+    #[bits(13)]
+    __: u32,
+
+    #[bits(1, r, get = has_counted_to_zero)]
+    pub(super) COUNTFLAG: bool,
+
+    #[bits(15)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct ReloadValueRegister {
+    #[bits(24, rw, get = get_reload_value, set = set_reload_value)]
+    pub(super) RELOAD: u32,
+
+    #[bits(8)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct CurrentValueRegister {
+    #[bits(24, rw, get = get_current_value, set = clear_current_value)]
+    pub(super) CURRENT: u32,
+
+    #[bits(8)]
+    __: u32,
+}
+
+#[register(u32)]
+pub(super) struct CalibrationValueRegister {
+    #[bits(24, r, get = get_ten_ms_reload_value)]
+    pub(super) TENMS: u32,
+
+    #[bits(6)]
+    __: u32,
+
+    #[bits(1, r, get = is_calibration_value_inexact)]
+    pub(super) SKEW: bool,
+
+    #[bits(1, r, get = has_no_reference_clock)]
+    pub(super) NOREF: bool,
+}
+
+#[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSource {
+    /// AHB clock divided by 8
+    HCLKDividedBy8 = 0b0,
+
+    /// Processor clock (HCLK)
+    HCLK = 0b1,
+}
@@ -0,0 +1,56 @@
+#![allow(dead_code)]
+
+use crate::peripheral;
+
+use self::register::*;
+
+mod register;
+
+pub fn dbgmcu() -> &'static mut DBGMCU {
+    peripheral(0xe004_2000)
+}
+
+pub struct DBGMCU {
+    /// ID Code Register
+    pub idcode: IdCodeRegister,
+
+    /// Control Register
+    __reserved0: u32,
+
+    /// APB1 Freeze Register
+    __reserved1: u32,
+
+    /// APB2 Freeze Register
+    __reserved2: u32,
+}
+
+impl DBGMCU {
+    /// Device ID of the STM32F42x and STM32F43x lines
+    pub const DEVICE_ID_STM32F42X: u16 = 0x419;
+
+    #[inline]
+    pub fn device_id(&self) -> u16 {
+        self.idcode.get_device_id()
+    }
+
+    #[inline]
+    pub fn revision_id(&self) -> u16 {
+        self.idcode.get_revision_id()
+    }
+
+    /// Silicon revision as printed on the package
+    pub fn revision(&self) -> Option<char> {
+        revision_name(self.device_id(), self.revision_id())
+    }
+}
+
+/// Maps a REV_ID to the revision letter used in the errata sheet
+pub fn revision_name(device_id: u16, revision_id: u16) -> Option<char> {
+    match (device_id, revision_id) {
+        (DBGMCU::DEVICE_ID_STM32F42X, 0x1000) => Some('A'),
+        (DBGMCU::DEVICE_ID_STM32F42X, 0x1003) => Some('Y'),
+        (DBGMCU::DEVICE_ID_STM32F42X, 0x1007) => Some('1'),
+        (DBGMCU::DEVICE_ID_STM32F42X, 0x2001) => Some('3'),
+        _ => None,
+    }
+}
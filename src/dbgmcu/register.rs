@@ -0,0 +1,13 @@
+use register::register;
+
+#[register(u32)]
+pub struct IdCodeRegister {
+    #[bits(12, r, get = get_device_id)]
+    pub DEV_ID: u16,
+
+    #[bits(4)]
+    __: u32,
+
+    #[bits(16, r, get = get_revision_id)]
+    pub REV_ID: u16,
+}
@@ -2,6 +2,8 @@ use core::ops::{ BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign,
 
 use register::field::RegisterField;
 
+use crate::interrupt::Interrupt;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Line {
     LINE0 = 0b0000_0000_0000_0000_0000_0001,
@@ -29,6 +31,35 @@ pub enum Line {
     LINE22 = 0b0100_0000_0000_0000_0000_0000,
 }
 
+impl Line {
+    /// Interrupt the line is routed to
+    pub fn interrupt(self) -> Interrupt {
+        match self {
+            Line::LINE0 => Interrupt::EXTI0,
+            Line::LINE1 => Interrupt::EXTI1,
+            Line::LINE2 => Interrupt::EXTI2,
+            Line::LINE3 => Interrupt::EXTI3,
+            Line::LINE4 => Interrupt::EXTI4,
+            Line::LINE5 | Line::LINE6 | Line::LINE7 | Line::LINE8 | Line::LINE9 => {
+                Interrupt::EXTI9_5
+            }
+            | Line::LINE10
+            | Line::LINE11
+            | Line::LINE12
+            | Line::LINE13
+            | Line::LINE14
+            | Line::LINE15 => Interrupt::EXTI15_10,
+            Line::LINE16 => Interrupt::PVD,
+            Line::LINE17 => Interrupt::RTC_Alarm,
+            Line::LINE18 => Interrupt::OTG_FS_WKUP,
+            Line::LINE19 => Interrupt::ETH_WKUP,
+            Line::LINE20 => Interrupt::OTG_HS_WKUP,
+            Line::LINE21 => Interrupt::TAMP_STAMP,
+            Line::LINE22 => Interrupt::RTC_WKUP,
+        }
+    }
+}
+
 impl Not for Line {
     type Output = LineMask;
 
@@ -47,6 +47,29 @@ impl EXTI {
         self.imr.set_interrupt_mask(lines | mask);
     }
 
+    #[inline]
+    pub fn mask_events_lines(&mut self, lines: impl Into<LineMask>) {
+        let mask = lines.into();
+        let lines = self.emr.get_event_mask();
+        self.emr.set_event_mask(lines & !mask);
+    }
+
+    /// Lets the lines generate events, which wake up the core from WFE
+    /// without entering an interrupt handler
+    #[inline]
+    pub fn unmask_events_lines(&mut self, lines: impl Into<LineMask>) {
+        let mask = lines.into();
+        let lines = self.emr.get_event_mask();
+        self.emr.set_event_mask(lines | mask);
+    }
+
+    /// Clears the pending flag of the lines, the others are left untouched
+    #[inline]
+    pub fn clear_pending_lines(&mut self, lines: impl Into<LineMask>) {
+        let mask: u32 = lines.into().into();
+        self.pr.set(mask);
+    }
+
     #[inline]
     pub fn set_rising_trigger_lines(&mut self, lines: impl Into<LineMask>) {
         let mask = lines.into();
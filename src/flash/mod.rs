@@ -0,0 +1,862 @@
+#![allow(dead_code)]
+
+use core::{ fmt, ptr };
+
+use crate::{ cortex_m::asm, identity::flash_size_kb, peripheral };
+
+use self::register::*;
+
+pub use self::register::{ BorLevel, Parallelism };
+
+mod register;
+
+pub fn flash() -> &'static mut FLASH {
+    peripheral(0x4002_3c00)
+}
+
+#[derive(Debug, Default)]
+pub struct FLASH {
+    /// Access Control Register
+    pub acr: AccessControlRegister,
+
+    /// Key Register
+    keyr: KeyRegister,
+
+    /// Option Key Register
+    optkeyr: OptionKeyRegister,
+
+    /// Status Register
+    sr: StatusRegister,
+
+    /// Control Register
+    cr: ControlRegister,
+
+    /// Option Control Register
+    optcr: OptionControlRegister,
+
+    /// Option Control Register 1
+    optcr1: OptionControlRegister1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Flash is locked, call `unlock` first
+    Locked,
+
+    /// Sector number does not exist
+    InvalidSector(u8),
+
+    /// Address is outside of the main memory
+    InvalidAddress(u32),
+
+    /// Address is not aligned to the programmed size
+    UnalignedAddress(u32),
+
+    /// Programming of an already written location (OPERR)
+    Operation,
+
+    /// Target is write protected (WRPERR)
+    WriteProtection,
+
+    /// Write crossed a 128-bit row (PGAERR)
+    ProgrammingAlignment,
+
+    /// Write size does not match PSIZE (PGPERR)
+    ProgrammingParallelism,
+
+    /// Write without PG set or while busy (PGSERR)
+    ProgrammingSequence,
+
+    /// Read of a PCROP protected sector (RDERR)
+    ReadProtection,
+
+    /// Option bytes are locked, call `unlock_options` first
+    OptionsLocked,
+
+    /// Read-out protection level 2 can only be set by `lock_permanently`
+    PermanentProtection,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Locked => f.write_str("Flash locked"),
+            Error::InvalidSector(s) => f.write_fmt(format_args!("Invalid sector: {}", s)),
+            Error::InvalidAddress(a) => f.write_fmt(format_args!("Invalid address: {:#010x}", a)),
+            Error::UnalignedAddress(a) =>
+                f.write_fmt(format_args!("Unaligned address: {:#010x}", a)),
+            Error::Operation => f.write_str("Operation error"),
+            Error::WriteProtection => f.write_str("Write protection error"),
+            Error::ProgrammingAlignment => f.write_str("Programming alignment error"),
+            Error::ProgrammingParallelism => f.write_str("Programming parallelism error"),
+            Error::ProgrammingSequence => f.write_str("Programming sequence error"),
+            Error::ReadProtection => f.write_str("Read protection error"),
+            Error::OptionsLocked => f.write_str("Option bytes locked"),
+            Error::PermanentProtection => f.write_str("Permanent protection refused"),
+        }
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Supply voltage range, it limits the HCLK frequency per flash wait state
+/// and the programming parallelism
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoltageRange {
+    /// 1.8 V to 2.1 V
+    V1_8To2_1,
+
+    /// 2.1 V to 2.4 V
+    V2_1To2_4,
+
+    /// 2.4 V to 2.7 V
+    V2_4To2_7,
+
+    /// 2.7 V to 3.6 V
+    V2_7To3_6,
+}
+
+impl VoltageRange {
+    /// Highest HCLK frequency that can be used with zero wait states
+    fn max_freq_per_wait_state(self) -> u32 {
+        match self {
+            VoltageRange::V1_8To2_1 => 20_000_000,
+            VoltageRange::V2_1To2_4 => 22_000_000,
+            VoltageRange::V2_4To2_7 => 24_000_000,
+            VoltageRange::V2_7To3_6 => 30_000_000,
+        }
+    }
+
+    /// Widest program/erase size supported without an external VPP
+    pub fn parallelism(self) -> Parallelism {
+        match self {
+            VoltageRange::V1_8To2_1 => Parallelism::X8,
+            VoltageRange::V2_1To2_4 => Parallelism::X16,
+            VoltageRange::V2_4To2_7 | VoltageRange::V2_7To3_6 => Parallelism::X32,
+        }
+    }
+}
+
+/// Number of wait states needed to read flash at `hclk`
+pub fn wait_states(hclk: u32, range: VoltageRange) -> u8 {
+    let step = range.max_freq_per_wait_state();
+
+    (hclk.saturating_sub(1) / step) as u8
+}
+
+/// Start of the main memory
+pub const FLASH_BASE: u32 = 0x0800_0000;
+
+/// Size of one bank of the 2 MiB parts
+pub const BANK_SIZE: u32 = 0x0010_0000;
+
+/// Number of sectors per bank
+pub const SECTORS_PER_BANK: u8 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bank {
+    Bank1,
+    Bank2,
+}
+
+/// Organisation of the main memory.
+///
+/// 2 MiB parts always have two banks, 1 MiB parts only when the DB1M option
+/// bit is set, with bank 2 starting at `0x0808_0000`. Bank 2 sectors are
+/// numbered from 12 whatever the bank size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    /// Size of the main memory in bytes
+    pub size: u32,
+
+    pub dual_bank: bool,
+}
+
+impl Layout {
+    /// Layout of a part with `size_kb` KiB of flash and the DB1M option bit
+    /// set to `db1m`
+    pub const fn new(size_kb: u16, db1m: bool) -> Self {
+        let size = size_kb as u32 * 1024;
+
+        Self { size, dual_bank: size == 2 * BANK_SIZE || (size == BANK_SIZE && db1m) }
+    }
+
+    #[inline]
+    fn bank_size(&self) -> u32 {
+        if self.dual_bank { self.size / 2 } else { self.size }
+    }
+
+    /// Sector `number`, `InvalidSector` if the part does not have it
+    pub fn sector(&self, number: u8) -> Result<Sector> {
+        let bank = number / SECTORS_PER_BANK;
+        if bank >= 2 || (bank == 1 && !self.dual_bank) {
+            return Err(Error::InvalidSector(number));
+        }
+
+        let (offset, size) = match number % SECTORS_PER_BANK {
+            n @ 0..=3 => (n as u32 * 0x4000, 0x4000),
+            4 => (0x1_0000, 0x1_0000),
+            n => ((n as u32 - 4) * 0x2_0000, 0x2_0000),
+        };
+        if offset + size > self.bank_size() {
+            return Err(Error::InvalidSector(number));
+        }
+
+        let base = FLASH_BASE + bank as u32 * self.bank_size() + offset;
+
+        Ok(Sector { number, base, size })
+    }
+
+    /// Sector containing `addr`
+    pub fn sector_at(&self, addr: u32) -> Result<Sector> {
+        if !(FLASH_BASE..FLASH_BASE + self.size).contains(&addr) {
+            return Err(Error::InvalidAddress(addr));
+        }
+
+        (0..2 * SECTORS_PER_BANK)
+            .filter_map(|n| self.sector(n).ok())
+            .find(|s| s.contains(addr))
+            .ok_or(Error::InvalidAddress(addr))
+    }
+}
+
+/// Main memory sector, per bank four 16 KiB, one 64 KiB and up to seven
+/// 128 KiB sectors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sector {
+    pub number: u8,
+    pub base: u32,
+    pub size: u32,
+}
+
+impl Sector {
+
+    #[inline]
+    pub fn contains(&self, addr: u32) -> bool {
+        addr >= self.base && addr - self.base < self.size
+    }
+
+    #[inline]
+    pub fn bank(&self) -> Bank {
+        if self.number < SECTORS_PER_BANK { Bank::Bank1 } else { Bank::Bank2 }
+    }
+
+    /// Value of the CR SNB field, bank 2 sectors start at `0b10000`
+    #[inline]
+    fn snb(&self) -> u8 {
+        match self.bank() {
+            Bank::Bank1 => self.number,
+            Bank::Bank2 => 0b10000 | (self.number - SECTORS_PER_BANK),
+        }
+    }
+}
+
+/// Maps the error flags of SR to an error, the first one found wins
+pub fn check_errors(sr: u32) -> Result<()> {
+    const ERRORS: [(u32, Error); 6] = [
+        (1 << 4, Error::WriteProtection),
+        (1 << 5, Error::ProgrammingAlignment),
+        (1 << 6, Error::ProgrammingParallelism),
+        (1 << 7, Error::ProgrammingSequence),
+        (1 << 8, Error::ReadProtection),
+        (1 << 1, Error::Operation),
+    ];
+
+    match ERRORS.iter().find(|(mask, _)| sr & mask != 0) {
+        Some(&(_, e)) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Read-out protection level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadProtection {
+    /// No protection
+    Level0,
+
+    /// Debug access and boot from RAM or system memory lock flash, going
+    /// back to level 0 mass erases the flash
+    Level1,
+
+    /// Debug and boot from RAM or system memory permanently disabled, can
+    /// never be undone
+    Level2,
+}
+
+impl ReadProtection {
+    const LEVEL0: u8 = 0xaa;
+    const LEVEL1: u8 = 0x55;
+    const LEVEL2: u8 = 0xcc;
+
+    /// Every RDP value other than `0xaa` and `0xcc` means level 1
+    pub fn from_bits(rdp: u8) -> Self {
+        match rdp {
+            Self::LEVEL0 => ReadProtection::Level0,
+            Self::LEVEL2 => ReadProtection::Level2,
+            _ => ReadProtection::Level1,
+        }
+    }
+
+    pub fn into_bits(self) -> u8 {
+        match self {
+            ReadProtection::Level0 => Self::LEVEL0,
+            ReadProtection::Level1 => Self::LEVEL1,
+            ReadProtection::Level2 => Self::LEVEL2,
+        }
+    }
+}
+
+/// Meaning of the per-sector nWRP bits, selected by SPRMOD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectorProtection {
+    /// Protected sectors cannot be programmed or erased
+    WriteProtection,
+
+    /// Protected sectors can only be fetched as code (PCROP)
+    ReadProtection,
+}
+
+/// Decoded OPTCR/OPTCR1 user option bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionBytes {
+    pub read_protection: ReadProtection,
+    pub bor_level: BorLevel,
+
+    /// Independent watchdog started by software instead of at reset
+    pub watchdog_software: bool,
+
+    /// Entering Stop mode does not generate a reset
+    pub no_reset_on_stop: bool,
+
+    /// Entering Standby mode does not generate a reset
+    pub no_reset_on_standby: bool,
+
+    /// Boot from bank 2 when BOOT0 is low and BOOT1 is high
+    pub boot_from_bank2: bool,
+
+    /// Split 1 MiB parts into two banks of eight sectors
+    pub dual_bank_1m: bool,
+
+    pub sector_protection: SectorProtection,
+
+    /// Protected sectors, bit `n` for sector `n`
+    pub protected_sectors: u32,
+}
+
+/// OPTCR bits that hold option bytes, i.e. everything but OPTLOCK and OPTSTRT
+const OPTCR_MASK: u32 = 0xcfff_fffc;
+
+/// Both banks' nWRP bits
+const SECTORS_MASK: u32 = 0x00ff_ffff;
+
+impl OptionBytes {
+    pub fn from_bits(optcr: u32, optcr1: u32) -> Self {
+        let sector_protection = if optcr & (1 << 31) != 0 {
+            SectorProtection::ReadProtection
+        } else {
+            SectorProtection::WriteProtection
+        };
+
+        let nwrp = ((optcr >> 16) & 0xfff) | (((optcr1 >> 16) & 0xfff) << 12);
+        let protected_sectors = match sector_protection {
+            SectorProtection::WriteProtection => !nwrp & SECTORS_MASK,
+            SectorProtection::ReadProtection => nwrp,
+        };
+
+        let bor_level = match (optcr >> 2) & 0b11 {
+            0b00 => BorLevel::Level3,
+            0b01 => BorLevel::Level2,
+            0b10 => BorLevel::Level1,
+            _ => BorLevel::Off,
+        };
+
+        Self {
+            read_protection: ReadProtection::from_bits((optcr >> 8) as u8),
+            bor_level,
+            watchdog_software: optcr & (1 << 5) != 0,
+            no_reset_on_stop: optcr & (1 << 6) != 0,
+            no_reset_on_standby: optcr & (1 << 7) != 0,
+            boot_from_bank2: optcr & (1 << 4) != 0,
+            dual_bank_1m: optcr & (1 << 30) != 0,
+            sector_protection,
+            protected_sectors,
+        }
+    }
+
+    /// Returns the OPTCR and OPTCR1 values, with OPTLOCK and OPTSTRT clear
+    pub fn to_bits(&self) -> (u32, u32) {
+        let nwrp = match self.sector_protection {
+            SectorProtection::WriteProtection => !self.protected_sectors & SECTORS_MASK,
+            SectorProtection::ReadProtection => self.protected_sectors & SECTORS_MASK,
+        };
+
+        let optcr =
+            ((self.bor_level as u32) << 2) |
+            ((self.boot_from_bank2 as u32) << 4) |
+            ((self.watchdog_software as u32) << 5) |
+            ((self.no_reset_on_stop as u32) << 6) |
+            ((self.no_reset_on_standby as u32) << 7) |
+            ((self.read_protection.into_bits() as u32) << 8) |
+            ((nwrp & 0xfff) << 16) |
+            ((self.dual_bank_1m as u32) << 30) |
+            (((self.sector_protection == SectorProtection::ReadProtection) as u32) << 31);
+
+        let optcr1 = ((nwrp >> 12) & 0xfff) << 16;
+
+        (optcr, optcr1)
+    }
+
+    #[inline]
+    pub fn sector_is_protected(&self, sector: u8) -> bool {
+        self.protected_sectors & (1 << sector) != 0
+    }
+
+    pub fn protect_sectors(mut self, mask: u32) -> Self {
+        self.protected_sectors |= mask & SECTORS_MASK;
+        self
+    }
+
+    pub fn unprotect_sectors(mut self, mask: u32) -> Self {
+        self.protected_sectors &= !mask;
+        self
+    }
+}
+
+/// EOP and all error flags of SR
+const SR_FLAGS: u32 = 0x0000_01f3;
+
+const KEY1: u32 = 0x4567_0123;
+const KEY2: u32 = 0xcdef_89ab;
+
+const OPTKEY1: u32 = 0x0819_2a3b;
+const OPTKEY2: u32 = 0x4c5d_6e7f;
+
+impl FLASH {
+    /// Highest number of wait states supported by the flash interface
+    pub const MAX_LATENCY: u8 = 15;
+
+    #[inline]
+    pub fn get_latency(&self) -> u8 {
+        self.acr.get_latency()
+    }
+
+    /// Sets the number of wait states and waits for the new value to take
+    /// effect
+    pub fn set_latency(&mut self, latency: u8) {
+        self.acr.set_latency(latency);
+
+        while self.acr.get_latency() != latency {}
+    }
+
+    /// Sets the wait states needed for `hclk` at the given supply voltage
+    pub fn configure_latency(&mut self, hclk: u32, range: VoltageRange) {
+        self.set_latency(wait_states(hclk, range));
+    }
+
+    /// Enables the prefetch buffer and the instruction and data caches
+    pub fn enable_acceleration(&mut self) {
+        self.acr.enable_prefetch();
+        self.acr.enable_instruction_cache();
+        self.acr.enable_data_cache();
+    }
+
+    /// Flushes both caches, needed after erasing or programming code or data
+    /// that might be cached
+    pub fn reset_caches(&mut self) {
+        let icache = self.acr.instruction_cache_is_enabled();
+        let dcache = self.acr.data_cache_is_enabled();
+
+        self.acr.disable_instruction_cache();
+        self.acr.disable_data_cache();
+
+        self.acr.set_instruction_cache_reset(true);
+        self.acr.set_data_cache_reset(true);
+        self.acr.set_instruction_cache_reset(false);
+        self.acr.set_data_cache_reset(false);
+
+        if icache {
+            self.acr.enable_instruction_cache();
+        }
+        if dcache {
+            self.acr.enable_data_cache();
+        }
+    }
+
+    #[inline]
+    pub fn is_locked(&self) -> bool {
+        self.cr.is_locked()
+    }
+
+    /// Unlocks the control register for program and erase operations
+    pub fn unlock(&mut self) {
+        if self.cr.is_locked() {
+            self.keyr.set_key(KEY1);
+            self.keyr.set_key(KEY2);
+        }
+    }
+
+    #[inline]
+    pub fn lock(&mut self) {
+        self.cr.set_lock(true);
+    }
+
+    #[inline]
+    pub fn is_busy(&self) -> bool {
+        self.sr.is_busy()
+    }
+
+    fn wait_ready(&self) {
+        while self.sr.is_busy() {}
+    }
+
+    /// Clears EOP and the error flags left by a previous operation
+    #[inline]
+    pub fn clear_status(&mut self) {
+        self.sr.set(SR_FLAGS);
+    }
+
+    fn prepare(&mut self) -> Result<()> {
+        if self.cr.is_locked() {
+            return Err(Error::Locked);
+        }
+
+        self.wait_ready();
+        self.clear_status();
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.wait_ready();
+
+        let sr = self.sr.get();
+        self.clear_status();
+
+        check_errors(sr)
+    }
+
+    /// Erases one sector. Erasing runs from the other bank without stalling,
+    /// erasing the bank the code runs from stalls the CPU until it is done.
+    pub fn erase_sector(&mut self, number: u8, range: VoltageRange) -> Result<()> {
+        let sector = self.layout().sector(number)?;
+
+        self.prepare()?;
+
+        self.cr.set_parallelism(range.parallelism());
+        self.cr.set_sector_erase(true);
+        self.cr.set_sector_number(sector.snb());
+        self.cr.set_start(true);
+
+        let r = self.finish();
+
+        self.cr.set_sector_erase(false);
+        self.cr.set_sector_number(0);
+
+        r
+    }
+
+    /// Erases a whole bank
+    pub fn erase_bank(&mut self, bank: Bank, range: VoltageRange) -> Result<()> {
+        self.prepare()?;
+
+        self.cr.set_parallelism(range.parallelism());
+        match bank {
+            Bank::Bank1 => self.cr.set_bank1_mass_erase(true),
+            Bank::Bank2 => self.cr.set_bank2_mass_erase(true),
+        }
+        self.cr.set_start(true);
+
+        let r = self.finish();
+
+        self.cr.set_bank1_mass_erase(false);
+        self.cr.set_bank2_mass_erase(false);
+
+        r
+    }
+
+    /// Erases both banks, must run from RAM
+    pub fn erase_all(&mut self, range: VoltageRange) -> Result<()> {
+        self.prepare()?;
+
+        self.cr.set_parallelism(range.parallelism());
+        self.cr.set_bank1_mass_erase(true);
+        self.cr.set_bank2_mass_erase(true);
+        self.cr.set_start(true);
+
+        let r = self.finish();
+
+        self.cr.set_bank1_mass_erase(false);
+        self.cr.set_bank2_mass_erase(false);
+
+        r
+    }
+
+    fn program_with<T>(&mut self, addr: u32, value: T, psize: Parallelism) -> Result<()> {
+        let size = core::mem::size_of::<T>() as u32;
+
+        self.layout().sector_at(addr)?;
+        if addr % size != 0 {
+            return Err(Error::UnalignedAddress(addr));
+        }
+
+        self.prepare()?;
+
+        self.cr.set_parallelism(psize);
+        self.cr.set_programming(true);
+
+        unsafe { ptr::write_volatile(addr as *mut T, value) }
+        asm::dsb();
+
+        let r = self.finish();
+
+        self.cr.set_programming(false);
+
+        r
+    }
+
+    #[inline]
+    pub fn program_u8(&mut self, addr: u32, value: u8) -> Result<()> {
+        self.program_with(addr, value, Parallelism::X8)
+    }
+
+    #[inline]
+    pub fn program_u16(&mut self, addr: u32, value: u16) -> Result<()> {
+        self.program_with(addr, value, Parallelism::X16)
+    }
+
+    #[inline]
+    pub fn program_u32(&mut self, addr: u32, value: u32) -> Result<()> {
+        self.program_with(addr, value, Parallelism::X32)
+    }
+
+    /// Programs a double word, only possible with an external VPP supply
+    #[inline]
+    pub fn program_u64(&mut self, addr: u32, value: u64) -> Result<()> {
+        self.program_with(addr, value, Parallelism::X64)
+    }
+
+    /// Programs `data` at `addr`, using the widest writes the alignment and
+    /// the supply voltage allow
+    pub fn program(&mut self, addr: u32, data: &[u8], range: VoltageRange) -> Result<()> {
+        let parallelism = range.parallelism();
+
+        let mut offset = 0;
+        while offset < data.len() {
+            let at = addr + offset as u32;
+            let rest = &data[offset..];
+
+            offset += if parallelism == Parallelism::X32 && at % 4 == 0 && rest.len() >= 4 {
+                self.program_u32(at, u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]))?;
+                4
+            } else if parallelism != Parallelism::X8 && at % 2 == 0 && rest.len() >= 2 {
+                self.program_u16(at, u16::from_le_bytes([rest[0], rest[1]]))?;
+                2
+            } else {
+                self.program_u8(at, rest[0])?;
+                1
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Main memory layout of this part, from the flash size register and
+    /// the DB1M option bit
+    pub fn layout(&self) -> Layout {
+        Layout::new(flash_size_kb(), self.optcr.dual_bank_1m_is_set())
+    }
+
+    #[inline]
+    pub fn options_are_locked(&self) -> bool {
+        self.optcr.options_are_locked()
+    }
+
+    /// Unlocks OPTCR/OPTCR1 for `write_options`
+    pub fn unlock_options(&mut self) {
+        if self.optcr.options_are_locked() {
+            self.optkeyr.set_key(OPTKEY1);
+            self.optkeyr.set_key(OPTKEY2);
+        }
+    }
+
+    #[inline]
+    pub fn lock_options(&mut self) {
+        self.optcr.set_options_lock(true);
+    }
+
+    pub fn read_options(&self) -> OptionBytes {
+        OptionBytes::from_bits(self.optcr.get(), self.optcr1.get())
+    }
+
+    /// Programs the option bytes, most of them take effect after the next
+    /// reset.
+    ///
+    /// Lowering read-out protection from level 1 to level 0 mass erases the
+    /// flash. Level 2 is refused here, see `lock_permanently`.
+    pub fn write_options(&mut self, options: &OptionBytes) -> Result<()> {
+        if options.read_protection == ReadProtection::Level2 {
+            return Err(Error::PermanentProtection);
+        }
+
+        self.commit_options(options)
+    }
+
+    /// Sets read-out protection level 2. The debug interface and boot from
+    /// RAM or system memory are disabled forever, including for this crate's
+    /// own option byte API.
+    pub fn lock_permanently(&mut self) -> Result<()> {
+        let options = OptionBytes {
+            read_protection: ReadProtection::Level2,
+            ..self.read_options()
+        };
+
+        self.commit_options(&options)
+    }
+
+    fn commit_options(&mut self, options: &OptionBytes) -> Result<()> {
+        if self.optcr.options_are_locked() {
+            return Err(Error::OptionsLocked);
+        }
+
+        self.wait_ready();
+        self.clear_status();
+
+        let (optcr, optcr1) = options.to_bits();
+        let keep = self.optcr.get() & !OPTCR_MASK;
+
+        self.optcr1.set(optcr1);
+        self.optcr.set(keep | (optcr & OPTCR_MASK));
+        self.optcr.set_options_start(true);
+
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wait_states() {
+        assert_eq!(wait_states(16_000_000, VoltageRange::V2_7To3_6), 0);
+        assert_eq!(wait_states(30_000_000, VoltageRange::V2_7To3_6), 0);
+        assert_eq!(wait_states(30_000_001, VoltageRange::V2_7To3_6), 1);
+        assert_eq!(wait_states(168_000_000, VoltageRange::V2_7To3_6), 5);
+        assert_eq!(wait_states(180_000_000, VoltageRange::V2_7To3_6), 5);
+        assert_eq!(wait_states(168_000_000, VoltageRange::V1_8To2_1), 8);
+        assert_eq!(wait_states(120_000_000, VoltageRange::V2_4To2_7), 4);
+    }
+
+    #[test]
+    fn test_sector_map() {
+        let layout = Layout::new(2048, false);
+        assert!(layout.dual_bank);
+
+        let sector = |number| layout.sector(number);
+        assert_eq!(sector(0), Ok(Sector { number: 0, base: 0x0800_0000, size: 0x4000 }));
+        assert_eq!(sector(3), Ok(Sector { number: 3, base: 0x0800_c000, size: 0x4000 }));
+        assert_eq!(sector(4), Ok(Sector { number: 4, base: 0x0801_0000, size: 0x1_0000 }));
+        assert_eq!(sector(5), Ok(Sector { number: 5, base: 0x0802_0000, size: 0x2_0000 }));
+        assert_eq!(sector(11), Ok(Sector { number: 11, base: 0x080e_0000, size: 0x2_0000 }));
+        assert_eq!(sector(12), Ok(Sector { number: 12, base: 0x0810_0000, size: 0x4000 }));
+        assert_eq!(sector(23), Ok(Sector { number: 23, base: 0x081e_0000, size: 0x2_0000 }));
+        assert_eq!(sector(24), Err(Error::InvalidSector(24)));
+
+        assert_eq!(sector(11).unwrap().snb(), 11);
+        assert_eq!(sector(12).unwrap().snb(), 0b10000);
+        assert_eq!(sector(23).unwrap().snb(), 0b11011);
+
+        assert_eq!(layout.sector_at(0x0800_0000).unwrap().number, 0);
+        assert_eq!(layout.sector_at(0x0801_ffff).unwrap().number, 4);
+        assert_eq!(layout.sector_at(0x0810_4000).unwrap().number, 13);
+        assert_eq!(layout.sector_at(0x081f_ffff).unwrap().number, 23);
+        assert_eq!(layout.sector_at(0x0820_0000), Err(Error::InvalidAddress(0x0820_0000)));
+        assert_eq!(layout.sector_at(0x2000_0000), Err(Error::InvalidAddress(0x2000_0000)));
+    }
+
+    #[test]
+    fn test_sector_map_1m() {
+        // single bank, nothing above 0x0810_0000
+        let layout = Layout::new(1024, false);
+        assert!(!layout.dual_bank);
+        assert_eq!(layout.sector(11).unwrap().base, 0x080e_0000);
+        assert_eq!(layout.sector(12), Err(Error::InvalidSector(12)));
+        assert_eq!(layout.sector_at(0x080f_ffff).unwrap().number, 11);
+        assert_eq!(layout.sector_at(0x0810_0000), Err(Error::InvalidAddress(0x0810_0000)));
+
+        // DB1M splits it into two 512 KiB banks of eight sectors
+        let layout = Layout::new(1024, true);
+        assert!(layout.dual_bank);
+        assert_eq!(layout.sector(7).unwrap().base, 0x0806_0000);
+        assert_eq!(layout.sector(8), Err(Error::InvalidSector(8)));
+        assert_eq!(layout.sector(12).unwrap().base, 0x0808_0000);
+        assert_eq!(layout.sector(19).unwrap().base, 0x080e_0000);
+        assert_eq!(layout.sector(20), Err(Error::InvalidSector(20)));
+        assert_eq!(layout.sector_at(0x0808_4000).unwrap().number, 13);
+        assert_eq!(layout.sector_at(0x0810_0000), Err(Error::InvalidAddress(0x0810_0000)));
+    }
+
+    #[test]
+    fn test_check_errors() {
+        assert_eq!(check_errors(0x0000_0001), Ok(()));
+        assert_eq!(check_errors(0x0001_0000), Ok(()));
+        assert_eq!(check_errors(1 << 4), Err(Error::WriteProtection));
+        assert_eq!(check_errors(1 << 5), Err(Error::ProgrammingAlignment));
+        assert_eq!(check_errors(1 << 6), Err(Error::ProgrammingParallelism));
+        assert_eq!(check_errors((1 << 7) | (1 << 6)), Err(Error::ProgrammingParallelism));
+        assert_eq!(check_errors(1 << 7), Err(Error::ProgrammingSequence));
+        assert_eq!(check_errors(1 << 8), Err(Error::ReadProtection));
+        assert_eq!(check_errors(1 << 1), Err(Error::Operation));
+    }
+
+    #[test]
+    fn test_parallelism() {
+        assert_eq!(VoltageRange::V1_8To2_1.parallelism(), Parallelism::X8);
+        assert_eq!(VoltageRange::V2_1To2_4.parallelism(), Parallelism::X16);
+        assert_eq!(VoltageRange::V2_7To3_6.parallelism(), Parallelism::X32);
+    }
+
+    #[test]
+    fn test_option_bytes() {
+        // reset value: level 0, BOR off, no protection, all user bits set
+        let options = OptionBytes::from_bits(0x0fff_aaed, 0x0fff_0000);
+        assert_eq!(options.read_protection, ReadProtection::Level0);
+        assert_eq!(options.bor_level, BorLevel::Off);
+        assert!(options.watchdog_software);
+        assert!(options.no_reset_on_stop);
+        assert!(options.no_reset_on_standby);
+        assert!(!options.boot_from_bank2);
+        assert!(!options.dual_bank_1m);
+        assert_eq!(options.sector_protection, SectorProtection::WriteProtection);
+        assert_eq!(options.protected_sectors, 0);
+        assert_eq!(options.to_bits(), (0x0fff_aaec, 0x0fff_0000));
+
+        // write protect sectors 0, 1 and 12, level 1, BOR level 2
+        let options = OptionBytes {
+            read_protection: ReadProtection::Level1,
+            bor_level: BorLevel::Level2,
+            ..options
+        }.protect_sectors(0b1_0000_0000_0011);
+        assert!(options.sector_is_protected(0));
+        assert!(options.sector_is_protected(12));
+        assert!(!options.sector_is_protected(2));
+
+        let (optcr, optcr1) = options.to_bits();
+        assert_eq!(optcr, 0x0ffc_55e4);
+        assert_eq!(optcr1, 0x0ffe_0000);
+        assert_eq!(OptionBytes::from_bits(optcr, optcr1), options);
+
+        // PCROP inverts the meaning of nWRP
+        let options = OptionBytes {
+            sector_protection: SectorProtection::ReadProtection,
+            ..options
+        };
+        let (optcr, optcr1) = options.to_bits();
+        assert_eq!(optcr, 0x8003_55e4);
+        assert_eq!(optcr1, 0x0001_0000);
+        assert_eq!(OptionBytes::from_bits(optcr, optcr1), options);
+
+        assert_eq!(ReadProtection::from_bits(0x12), ReadProtection::Level1);
+        assert_eq!(ReadProtection::from_bits(0xcc), ReadProtection::Level2);
+    }
+}
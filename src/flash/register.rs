@@ -0,0 +1,218 @@
+use register::{ field::derive::RegisterField, register };
+
+#[register(u32)]
+#[derive(Debug, Default)]
+pub struct AccessControlRegister {
+    #[bits(4, rw, get = get_latency, set = set_latency)]
+    pub LATENCY: u8,
+
+    #[bits(4)]
+    __: u32,
+
+    #[bits(1, rwc, get = prefetch_is_enabled, set = enable_prefetch, clear = disable_prefetch)]
+    pub PRFTEN: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = instruction_cache_is_enabled,
+        set = enable_instruction_cache,
+        clear = disable_instruction_cache
+    )]
+    pub ICEN: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = data_cache_is_enabled,
+        set = enable_data_cache,
+        clear = disable_data_cache
+    )]
+    pub DCEN: bool,
+
+    #[bits(1, w, set = set_instruction_cache_reset)]
+    pub ICRST: bool,
+
+    #[bits(1, w, set = set_data_cache_reset)]
+    pub DCRST: bool,
+
+    #[bits(19)]
+    __: u32,
+}
+
+#[register(u32)]
+#[derive(Debug, Default)]
+pub struct KeyRegister {
+    #[bits(32, w, set = set_key)]
+    pub KEY: u32,
+}
+
+#[register(u32)]
+#[derive(Debug, Default)]
+pub struct OptionKeyRegister {
+    #[bits(32, w, set = set_key)]
+    pub OPTKEY: u32,
+}
+
+/// Flags are cleared by writing `1`, use the raw `set` for that
+#[register(u32)]
+#[derive(Debug, Default)]
+pub struct StatusRegister {
+    #[bits(1, r, get = is_end_of_operation)]
+    pub EOP: bool,
+
+    #[bits(1, r, get = is_operation_error)]
+    pub OPERR: bool,
+
+    #[bits(2)]
+    __: u32,
+
+    #[bits(1, r, get = is_write_protection_error)]
+    pub WRPERR: bool,
+
+    #[bits(1, r, get = is_programming_alignment_error)]
+    pub PGAERR: bool,
+
+    #[bits(1, r, get = is_programming_parallelism_error)]
+    pub PGPERR: bool,
+
+    #[bits(1, r, get = is_programming_sequence_error)]
+    pub PGSERR: bool,
+
+    #[bits(1, r, get = is_read_protection_error)]
+    pub RDERR: bool,
+
+    #[bits(7)]
+    __: u32,
+
+    #[bits(1, r, get = is_busy)]
+    pub BSY: bool,
+
+    #[bits(15)]
+    __: u32,
+}
+
+#[register(u32)]
+#[derive(Debug, Default)]
+pub struct ControlRegister {
+    #[bits(1, rw, get = programming_is_enabled, set = set_programming)]
+    pub PG: bool,
+
+    #[bits(1, rw, get = sector_erase_is_enabled, set = set_sector_erase)]
+    pub SER: bool,
+
+    #[bits(1, rw, get = bank1_mass_erase_is_enabled, set = set_bank1_mass_erase)]
+    pub MER: bool,
+
+    #[bits(5, rw, get = get_sector_number, set = set_sector_number)]
+    pub SNB: u8,
+
+    #[bits(2, rw, get = get_parallelism, set = set_parallelism)]
+    pub PSIZE: Parallelism,
+
+    #[bits(5)]
+    __: u32,
+
+    #[bits(1, rw, get = bank2_mass_erase_is_enabled, set = set_bank2_mass_erase)]
+    pub MER1: bool,
+
+    #[bits(1, rw, get = is_started, set = set_start)]
+    pub STRT: bool,
+
+    #[bits(7)]
+    __: u32,
+
+    #[bits(
+        1,
+        rw,
+        get = end_of_operation_interrupt_is_enabled,
+        set = set_end_of_operation_interrupt
+    )]
+    pub EOPIE: bool,
+
+    #[bits(1, rw, get = error_interrupt_is_enabled, set = set_error_interrupt)]
+    pub ERRIE: bool,
+
+    #[bits(5)]
+    __: u32,
+
+    #[bits(1, rw, get = is_locked, set = set_lock)]
+    pub LOCK: bool,
+}
+
+#[register(u32)]
+#[derive(Debug, Default)]
+pub struct OptionControlRegister {
+    #[bits(1, rw, get = options_are_locked, set = set_options_lock)]
+    pub OPTLOCK: bool,
+
+    #[bits(1, rw, get = options_start_is_set, set = set_options_start)]
+    pub OPTSTRT: bool,
+
+    #[bits(2, rw, get = get_bor_level, set = set_bor_level)]
+    pub BOR_LEV: BorLevel,
+
+    #[bits(1, rw, get = boot_from_bank2_is_set, set = set_boot_from_bank2)]
+    pub BFB2: bool,
+
+    #[bits(1, rw, get = watchdog_is_software, set = set_watchdog_software)]
+    pub WDG_SW: bool,
+
+    #[bits(1, rw, get = no_reset_on_stop, set = set_no_reset_on_stop)]
+    pub NRST_STOP: bool,
+
+    #[bits(1, rw, get = no_reset_on_standby, set = set_no_reset_on_standby)]
+    pub NRST_STDBY: bool,
+
+    #[bits(8, rw, get = get_read_protection, set = set_read_protection)]
+    pub RDP: u8,
+
+    #[bits(12, rw, get = get_not_write_protect, set = set_not_write_protect)]
+    pub NWRP: u16,
+
+    #[bits(2)]
+    __: u32,
+
+    #[bits(1, rw, get = dual_bank_1m_is_set, set = set_dual_bank_1m)]
+    pub DB1M: bool,
+
+    #[bits(1, rw, get = pcrop_is_selected, set = set_pcrop_selected)]
+    pub SPRMOD: bool,
+}
+
+#[register(u32)]
+#[derive(Debug, Default)]
+pub struct OptionControlRegister1 {
+    #[bits(16)]
+    __: u32,
+
+    #[bits(12, rw, get = get_not_write_protect, set = set_not_write_protect)]
+    pub NWRP: u16,
+
+    #[bits(4)]
+    __: u32,
+}
+
+#[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parallelism {
+    X8 = 0b00,
+    X16 = 0b01,
+    X32 = 0b10,
+    X64 = 0b11,
+}
+
+/// Brown-out reset threshold
+#[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorLevel {
+    /// 2.70 V to 3.60 V
+    Level3 = 0b00,
+
+    /// 2.40 V to 2.70 V
+    Level2 = 0b01,
+
+    /// 2.10 V to 2.40 V
+    Level1 = 0b10,
+
+    /// 1.80 V to 2.10 V, BOR off
+    Off = 0b11,
+}
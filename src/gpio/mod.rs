@@ -137,38 +137,39 @@ impl GPIO {
                 self.ospeedr.set(ospeedr);
                 self.pupdr.set(pupdr);
 
-                match interrupt {
-                    InterruptType::None => (),
-                    InterruptType::RisingEdge => {
-                        let syscfg = SYSCFG::get();
-                        syscfg.set_external_interrupt_source(self.port(), mask);
-
-                        let exti = EXTI::get();
-                        exti.set_rising_trigger_lines(mask);
-                        exti.reset_falling_trigger_lines(mask);
-
-                        exti.unmask_interrupts_lines(mask);
-                    }
-                    InterruptType::FallingEdge => {
-                        let syscfg = SYSCFG::get();
-                        syscfg.set_external_interrupt_source(self.port(), mask);
-
-                        let exti = EXTI::get();
-                        exti.reset_rising_trigger_lines(mask);
-                        exti.set_falling_trigger_lines(mask);
-
-                        exti.unmask_interrupts_lines(mask);
+                let (rising, falling, event) = match interrupt {
+                    InterruptType::None => {
+                        return;
                     }
-                    InterruptType::RisingFallingEdge => {
-                        let syscfg = SYSCFG::get();
-                        syscfg.set_external_interrupt_source(self.port(), mask);
-
-                        let exti = EXTI::get();
-                        exti.set_rising_trigger_lines(mask);
-                        exti.set_falling_trigger_lines(mask);
+                    InterruptType::RisingEdge => (true, false, false),
+                    InterruptType::FallingEdge => (false, true, false),
+                    InterruptType::RisingFallingEdge => (true, true, false),
+                    InterruptType::RisingEdgeEvent => (true, false, true),
+                    InterruptType::FallingEdgeEvent => (false, true, true),
+                    InterruptType::RisingFallingEdgeEvent => (true, true, true),
+                };
+
+                let syscfg = SYSCFG::get();
+                syscfg.set_external_interrupt_source(self.port(), mask);
+
+                let exti = EXTI::get();
+                if rising {
+                    exti.set_rising_trigger_lines(mask);
+                } else {
+                    exti.reset_rising_trigger_lines(mask);
+                }
+                if falling {
+                    exti.set_falling_trigger_lines(mask);
+                } else {
+                    exti.reset_falling_trigger_lines(mask);
+                }
 
-                        exti.unmask_interrupts_lines(mask);
-                    }
+                if event {
+                    exti.mask_interrupts_lines(mask);
+                    exti.unmask_events_lines(mask);
+                } else {
+                    exti.mask_events_lines(mask);
+                    exti.unmask_interrupts_lines(mask);
                 }
             }
             PinConfig::Output(otype, ospeed, pull) => {
@@ -261,6 +262,12 @@ pub enum InterruptType {
     RisingEdge,
     FallingEdge,
     RisingFallingEdge,
+
+    /// Event variants raise an EXTI event instead of an interrupt, used to
+    /// wake up the core from `wait_for_event`
+    RisingEdgeEvent,
+    FallingEdgeEvent,
+    RisingFallingEdgeEvent,
 }
 
 pub enum PinConfig {
@@ -1,5 +1,7 @@
 use core::ptr::{ self, addr_of_mut };
 
+use crate::cortex_m::interrupt;
+
 use super::*;
 
 pub(super) static mut I2C1_STATE: State = State::new();
@@ -32,7 +34,7 @@ impl State {
     }
 }
 
-pub(super) unsafe fn state_mut(i2c: &I2C) -> *mut State {
+unsafe fn state_ptr(i2c: &I2C) -> *mut State {
     let ptr = i2c as *const I2C;
 
     match ptr as usize {
@@ -43,6 +45,11 @@ pub(super) unsafe fn state_mut(i2c: &I2C) -> *mut State {
     }
 }
 
+/// Gives `f` exclusive access to the driver state, masking interrupts meanwhile
+pub(super) fn state_mut<R>(i2c: &I2C, f: impl FnOnce(&mut State) -> R) -> R {
+    interrupt::free(|_| f(unsafe { &mut *state_ptr(i2c) }))
+}
+
 unsafe fn i2c_irq_event_handler(i2c: &mut I2C, state: *mut State) {
     let state = &mut *state;
 
@@ -5,7 +5,7 @@ use core::fmt::{ self };
 use irq::{state_mut, Status};
 use ::register::field::derive::RegisterField;
 
-use crate::{ peripheral, rcc::rcc, PeripheralClock };
+use crate::{ interrupt::Interrupt, peripheral, rcc::rcc, PeripheralClock };
 
 use self::register::*;
 
@@ -196,8 +196,7 @@ impl I2C {
     }
 
     pub fn master_write_data_begin(&mut self, addr: u8, data: &[u8]) -> Result<()> {
-        unsafe {
-            let state = &mut *state_mut(&self);
+        state_mut(self, |state| -> Result<()> {
             match state.status {
                 Status::Ready => Ok(()),
                 Status::BusyRx => Err(Error::BusyError("RX in progress")),
@@ -208,7 +207,9 @@ impl I2C {
             state.addr = addr;
             state.tx_buf = (data.as_ptr(), data.len());
             state.status = Status::BusyTx;
-        }
+
+            Ok(())
+        })?;
 
         self.cr1.generate_start_condition();
         self.cr2.enable_buffer_interrupt();
@@ -260,8 +261,7 @@ impl I2C {
     }
 
     pub fn master_read_data_begin(&mut self, addr: u8, data: &mut [u8]) -> Result<()> {
-        unsafe {
-            let state = &mut *state_mut(&self);
+        state_mut(self, |state| -> Result<()> {
             match state.status {
                 Status::Ready => Ok(()),
                 Status::BusyRx => Err(Error::BusyError("RX in progress")),
@@ -272,7 +272,9 @@ impl I2C {
             state.addr = addr;
             state.rx_buf = (data.as_mut_ptr(), data.len());
             state.status = Status::BusyRx;
-        }
+
+            Ok(())
+        })?;
 
         self.cr1.generate_start_condition();
         self.cr2.enable_buffer_interrupt();
@@ -281,6 +283,30 @@ impl I2C {
 
         Ok(())
     }
+
+    /// Event interrupt raised by this peripheral
+    pub fn event_interrupt(&self) -> Interrupt {
+        let ptr = self as *const Self;
+
+        match ptr as usize {
+            0x4000_5400 => Interrupt::I2C1_EV,
+            0x4000_5800 => Interrupt::I2C2_EV,
+            0x4000_5c00 => Interrupt::I2C3_EV,
+            _ => panic!(),
+        }
+    }
+
+    /// Error interrupt raised by this peripheral
+    pub fn error_interrupt(&self) -> Interrupt {
+        let ptr = self as *const Self;
+
+        match ptr as usize {
+            0x4000_5400 => Interrupt::I2C1_ER,
+            0x4000_5800 => Interrupt::I2C2_ER,
+            0x4000_5c00 => Interrupt::I2C3_ER,
+            _ => panic!(),
+        }
+    }
 }
 
 impl PeripheralClock for I2C {
@@ -0,0 +1,156 @@
+//! Device identity: core CPUID, the factory-programmed 96-bit unique ID, the
+//! flash size and the DBGMCU device/revision ID.
+
+use core::{ fmt, ptr };
+
+use crate::{ cortex_m::{ scb::CpuId, Peripherals }, dbgmcu::{ dbgmcu, revision_name } };
+
+/// Address of the 96-bit unique device ID
+const UID_ADDR: usize = 0x1fff_7a10;
+
+/// Address of the flash size register, in KiB
+const FLASH_SIZE_ADDR: usize = 0x1fff_7a22;
+
+/// 96-bit unique device ID, unique for every STM32 die
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UniqueId([u32; 3]);
+
+impl UniqueId {
+    pub fn read() -> Self {
+        let uid = UID_ADDR as *const u32;
+
+        unsafe {
+            Self([
+                ptr::read_volatile(uid),
+                ptr::read_volatile(uid.add(1)),
+                ptr::read_volatile(uid.add(2)),
+            ])
+        }
+    }
+
+    pub fn from_words(words: [u32; 3]) -> Self {
+        Self(words)
+    }
+
+    #[inline]
+    pub fn words(&self) -> [u32; 3] {
+        self.0
+    }
+
+    /// The ID as laid out in memory
+    pub fn to_bytes(&self) -> [u8; 12] {
+        let mut bytes = [0u8; 12];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(self.0) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Derives a stable, locally administered unicast MAC address
+    pub fn mac_address(&self) -> [u8; 6] {
+        let bytes = self.to_bytes();
+
+        let mut mac = [0u8; 6];
+        for (i, b) in mac.iter_mut().enumerate() {
+            *b = bytes[i] ^ bytes[i + 6];
+        }
+        mac[0] = (mac[0] & !0x01) | 0x02;
+
+        mac
+    }
+}
+
+/// Formats the ID as 24 hex digits, most significant word first, suitable as
+/// a serial number
+impl fmt::Display for UniqueId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("{:08X}{:08X}{:08X}", self.0[2], self.0[1], self.0[0]))
+    }
+}
+
+/// Size of the on-chip flash in KiB
+pub fn flash_size_kb() -> u16 {
+    unsafe { ptr::read_volatile(FLASH_SIZE_ADDR as *const u16) }
+}
+
+/// Everything that identifies the chip, read at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Identity {
+    pub cpuid: CpuId,
+    pub unique_id: UniqueId,
+    pub flash_size_kb: u16,
+    pub device_id: u16,
+    pub revision_id: u16,
+}
+
+impl Identity {
+    pub fn read() -> Self {
+        let dbgmcu = dbgmcu();
+
+        Self {
+            cpuid: Peripherals::scb().cpuid(),
+            unique_id: UniqueId::read(),
+            flash_size_kb: flash_size_kb(),
+            device_id: dbgmcu.device_id(),
+            revision_id: dbgmcu.revision_id(),
+        }
+    }
+
+    /// Silicon revision as printed on the package
+    pub fn revision(&self) -> Option<char> {
+        revision_name(self.device_id, self.revision_id)
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("device {:#05x} rev ", self.device_id))?;
+        match self.revision() {
+            Some(rev) => f.write_fmt(format_args!("{}", rev))?,
+            None => f.write_fmt(format_args!("{:#06x}", self.revision_id))?,
+        }
+        f.write_fmt(
+            format_args!(
+                ", {} KiB flash, UID {}, {}",
+                self.flash_size_kb,
+                self.unique_id,
+                self.cpuid
+            )
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_id() {
+        let uid = UniqueId::from_words([0x0033_0021, 0x3433_5109, 0x3136_3533]);
+
+        assert_eq!(
+            uid.to_bytes(),
+            [0x21, 0x00, 0x33, 0x00, 0x09, 0x51, 0x33, 0x34, 0x33, 0x35, 0x36, 0x31]
+        );
+
+        let mut s = [0u8; 24];
+        let mut w = Buffer(&mut s, 0);
+        fmt::Write::write_fmt(&mut w, format_args!("{}", uid)).unwrap();
+        assert_eq!(&s, b"313635333433510900330021");
+
+        let mac = uid.mac_address();
+        assert_eq!(mac, [0x12, 0x34, 0x00, 0x35, 0x3f, 0x60]);
+        assert_eq!(mac[0] & 0x03, 0x02);
+    }
+
+    struct Buffer<'a>(&'a mut [u8], usize);
+
+    impl<'a> fmt::Write for Buffer<'a> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.1 + s.len();
+            self.0.get_mut(self.1..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+            self.1 = end;
+            Ok(())
+        }
+    }
+}
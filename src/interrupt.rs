@@ -0,0 +1,292 @@
+use crate::cortex_m::nvic::InterruptNumber;
+
+/// STM32F42x device interrupts, numbered by their position in the vector table
+#[allow(non_camel_case_types)]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupt {
+    /// Window Watchdog interrupt
+    WWDG = 0,
+
+    /// PVD through EXTI line 16 interrupt
+    PVD = 1,
+
+    /// Tamper and TimeStamp through EXTI line 21 interrupt
+    TAMP_STAMP = 2,
+
+    /// RTC Wakeup through EXTI line 22 interrupt
+    RTC_WKUP = 3,
+
+    /// Flash global interrupt
+    FLASH = 4,
+
+    /// RCC global interrupt
+    RCC = 5,
+
+    /// EXTI Line0 interrupt
+    EXTI0 = 6,
+
+    /// EXTI Line1 interrupt
+    EXTI1 = 7,
+
+    /// EXTI Line2 interrupt
+    EXTI2 = 8,
+
+    /// EXTI Line3 interrupt
+    EXTI3 = 9,
+
+    /// EXTI Line4 interrupt
+    EXTI4 = 10,
+
+    /// DMA1 Stream0 global interrupt
+    DMA1_Stream0 = 11,
+
+    /// DMA1 Stream1 global interrupt
+    DMA1_Stream1 = 12,
+
+    /// DMA1 Stream2 global interrupt
+    DMA1_Stream2 = 13,
+
+    /// DMA1 Stream3 global interrupt
+    DMA1_Stream3 = 14,
+
+    /// DMA1 Stream4 global interrupt
+    DMA1_Stream4 = 15,
+
+    /// DMA1 Stream5 global interrupt
+    DMA1_Stream5 = 16,
+
+    /// DMA1 Stream6 global interrupt
+    DMA1_Stream6 = 17,
+
+    /// ADC1, ADC2 and ADC3 global interrupt
+    ADC = 18,
+
+    /// CAN1 TX interrupt
+    CAN1_TX = 19,
+
+    /// CAN1 RX0 interrupt
+    CAN1_RX0 = 20,
+
+    /// CAN1 RX1 interrupt
+    CAN1_RX1 = 21,
+
+    /// CAN1 SCE interrupt
+    CAN1_SCE = 22,
+
+    /// EXTI lines 5 to 9 interrupt
+    EXTI9_5 = 23,
+
+    /// TIM1 Break and TIM9 global interrupt
+    TIM1_BRK_TIM9 = 24,
+
+    /// TIM1 Update and TIM10 global interrupt
+    TIM1_UP_TIM10 = 25,
+
+    /// TIM1 Trigger and Commutation and TIM11 global interrupt
+    TIM1_TRG_COM_TIM11 = 26,
+
+    /// TIM1 Capture Compare interrupt
+    TIM1_CC = 27,
+
+    /// TIM2 global interrupt
+    TIM2 = 28,
+
+    /// TIM3 global interrupt
+    TIM3 = 29,
+
+    /// TIM4 global interrupt
+    TIM4 = 30,
+
+    /// I2C1 event interrupt
+    I2C1_EV = 31,
+
+    /// I2C1 error interrupt
+    I2C1_ER = 32,
+
+    /// I2C2 event interrupt
+    I2C2_EV = 33,
+
+    /// I2C2 error interrupt
+    I2C2_ER = 34,
+
+    /// SPI1 global interrupt
+    SPI1 = 35,
+
+    /// SPI2 global interrupt
+    SPI2 = 36,
+
+    /// USART1 global interrupt
+    USART1 = 37,
+
+    /// USART2 global interrupt
+    USART2 = 38,
+
+    /// USART3 global interrupt
+    USART3 = 39,
+
+    /// EXTI lines 10 to 15 interrupt
+    EXTI15_10 = 40,
+
+    /// RTC Alarms through EXTI line 17 interrupt
+    RTC_Alarm = 41,
+
+    /// USB On-The-Go FS Wakeup through EXTI line 18 interrupt
+    OTG_FS_WKUP = 42,
+
+    /// TIM8 Break and TIM12 global interrupt
+    TIM8_BRK_TIM12 = 43,
+
+    /// TIM8 Update and TIM13 global interrupt
+    TIM8_UP_TIM13 = 44,
+
+    /// TIM8 Trigger and Commutation and TIM14 global interrupt
+    TIM8_TRG_COM_TIM14 = 45,
+
+    /// TIM8 Capture Compare interrupt
+    TIM8_CC = 46,
+
+    /// DMA1 Stream7 global interrupt
+    DMA1_Stream7 = 47,
+
+    /// FMC global interrupt
+    FMC = 48,
+
+    /// SDIO global interrupt
+    SDIO = 49,
+
+    /// TIM5 global interrupt
+    TIM5 = 50,
+
+    /// SPI3 global interrupt
+    SPI3 = 51,
+
+    /// UART4 global interrupt
+    UART4 = 52,
+
+    /// UART5 global interrupt
+    UART5 = 53,
+
+    /// TIM6 global and DAC1/DAC2 underrun interrupt
+    TIM6_DAC = 54,
+
+    /// TIM7 global interrupt
+    TIM7 = 55,
+
+    /// DMA2 Stream0 global interrupt
+    DMA2_Stream0 = 56,
+
+    /// DMA2 Stream1 global interrupt
+    DMA2_Stream1 = 57,
+
+    /// DMA2 Stream2 global interrupt
+    DMA2_Stream2 = 58,
+
+    /// DMA2 Stream3 global interrupt
+    DMA2_Stream3 = 59,
+
+    /// DMA2 Stream4 global interrupt
+    DMA2_Stream4 = 60,
+
+    /// ETH global interrupt
+    ETH = 61,
+
+    /// Ethernet Wakeup through EXTI line 19 interrupt
+    ETH_WKUP = 62,
+
+    /// CAN2 TX interrupt
+    CAN2_TX = 63,
+
+    /// CAN2 RX0 interrupt
+    CAN2_RX0 = 64,
+
+    /// CAN2 RX1 interrupt
+    CAN2_RX1 = 65,
+
+    /// CAN2 SCE interrupt
+    CAN2_SCE = 66,
+
+    /// USB On-The-Go FS global interrupt
+    OTG_FS = 67,
+
+    /// DMA2 Stream5 global interrupt
+    DMA2_Stream5 = 68,
+
+    /// DMA2 Stream6 global interrupt
+    DMA2_Stream6 = 69,
+
+    /// DMA2 Stream7 global interrupt
+    DMA2_Stream7 = 70,
+
+    /// USART6 global interrupt
+    USART6 = 71,
+
+    /// I2C3 event interrupt
+    I2C3_EV = 72,
+
+    /// I2C3 error interrupt
+    I2C3_ER = 73,
+
+    /// USB On-The-Go HS End Point 1 Out interrupt
+    OTG_HS_EP1_OUT = 74,
+
+    /// USB On-The-Go HS End Point 1 In interrupt
+    OTG_HS_EP1_IN = 75,
+
+    /// USB On-The-Go HS Wakeup through EXTI line 20 interrupt
+    OTG_HS_WKUP = 76,
+
+    /// USB On-The-Go HS global interrupt
+    OTG_HS = 77,
+
+    /// DCMI global interrupt
+    DCMI = 78,
+
+    /// CRYP crypto global interrupt
+    CRYP = 79,
+
+    /// Hash and RNG global interrupt
+    HASH_RNG = 80,
+
+    /// FPU global interrupt
+    FPU = 81,
+
+    /// UART7 global interrupt
+    UART7 = 82,
+
+    /// UART8 global interrupt
+    UART8 = 83,
+
+    /// SPI4 global interrupt
+    SPI4 = 84,
+
+    /// SPI5 global interrupt
+    SPI5 = 85,
+
+    /// SPI6 global interrupt
+    SPI6 = 86,
+
+    /// SAI1 global interrupt
+    SAI1 = 87,
+
+    /// LTDC global interrupt
+    LTDC = 88,
+
+    /// LTDC global error interrupt
+    LTDC_ER = 89,
+
+    /// DMA2D global interrupt
+    DMA2D = 90,
+}
+
+impl Interrupt {
+    /// Number of device interrupts
+    pub const COUNT: usize = 91;
+}
+
+impl InterruptNumber for Interrupt {
+    #[inline]
+    fn number(self) -> u16 {
+        self as u16
+    }
+}
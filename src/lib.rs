@@ -3,13 +3,19 @@
 use register::field::derive::RegisterField;
 
 pub mod cortex_m;
+pub mod interrupt;
+pub mod dbgmcu;
+pub mod identity;
 pub mod syscfg;
 pub mod rcc;
+pub mod flash;
+pub mod pwr;
 pub mod exti;
 pub mod gpio;
 pub mod i2c;
 pub mod spi;
 pub mod usart;
+pub mod time;
 
 pub trait PeripheralClock {
     fn reset(&self);
@@ -23,6 +29,18 @@ pub enum State {
     ON,
 }
 
+/// Polling iterations before giving up on a ready flag
+pub(crate) const READY_TIMEOUT: u32 = 0x0010_0000;
+
+/// Polls `ready` up to `attempts` times, returns `error` if it never held
+pub(crate) fn wait_for<E>(
+    attempts: u32,
+    mut ready: impl FnMut() -> bool,
+    error: E
+) -> Result<(), E> {
+    if (0..attempts).any(|_| ready()) { Ok(()) } else { Err(error) }
+}
+
 #[inline(always)]
 pub(crate) fn peripheral<T>(addr: usize) -> &'static mut T {
     unsafe {
@@ -0,0 +1,431 @@
+#![allow(dead_code)]
+
+use core::fmt;
+
+use crate::{
+    cortex_m::{ asm, power::{ self, SleepMode } },
+    exti::{ line::{ Line, LineMask }, EXTI },
+    peripheral,
+    rcc::{ clocks, rcc, ClockConfig, Clocks },
+    wait_for,
+    PeripheralClock,
+    READY_TIMEOUT,
+};
+
+use self::register::*;
+
+pub use self::register::PvdLevel;
+
+mod register;
+
+pub fn pwr() -> &'static mut PWR {
+    peripheral(0x4000_7000)
+}
+
+#[derive(Debug, Default)]
+pub struct PWR {
+    /// Power Control Register
+    cr: PowerControlRegister,
+
+    /// Power Control/Status Register
+    csr: PowerControlAndStatusRegister,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// A regulator did not become ready in time
+    Timeout(&'static str),
+
+    /// Under-drive in Stop mode needs over-drive to be disabled first
+    OverDriveEnabled,
+
+    /// Clock tree could not be restored after Stop mode
+    Clocks(clocks::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Timeout(e) => f.write_fmt(format_args!("Timeout: {}", e)),
+            Error::OverDriveEnabled => f.write_str("Over-drive enabled"),
+            Error::Clocks(e) => f.write_fmt(format_args!("Clocks: {}", e)),
+        }
+    }
+}
+
+impl From<clocks::Error> for Error {
+    fn from(e: clocks::Error) -> Self {
+        Error::Clocks(e)
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Main regulator output voltage, trades power consumption for the highest
+/// possible HCLK
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoltageScale {
+    /// Up to 120 MHz
+    Scale3 = 0b01,
+
+    /// Up to 144 MHz, 168 MHz with over-drive
+    Scale2 = 0b10,
+
+    /// Up to 168 MHz, 180 MHz with over-drive
+    Scale1 = 0b11,
+}
+
+impl VoltageScale {
+    /// Lowest scale, and whether over-drive is needed, to run at `hclk`
+    pub fn for_frequency(hclk: u32) -> (Self, bool) {
+        match hclk {
+            0..=120_000_000 => (VoltageScale::Scale3, false),
+            120_000_001..=144_000_000 => (VoltageScale::Scale2, false),
+            144_000_001..=168_000_000 => (VoltageScale::Scale1, false),
+            _ => (VoltageScale::Scale1, true),
+        }
+    }
+}
+
+/// EXTI edge on which the PVD interrupt fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PvdTrigger {
+    /// VDD dropped below the threshold
+    Falling,
+
+    /// VDD rose above the threshold
+    Rising,
+
+    Both,
+}
+
+/// Regulator feeding the 1.2 V domain during Stop mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Regulator {
+    /// Faster wake-up
+    Main,
+
+    /// Lower consumption, longer wake-up
+    LowPower,
+}
+
+/// Stop mode configuration builder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StopConfig {
+    regulator: Regulator,
+    flash_power_down: bool,
+    under_drive: bool,
+    wait_for_event: bool,
+}
+
+impl Default for StopConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StopConfig {
+    /// Main regulator, flash powered, woken up by an interrupt
+    pub const fn new() -> Self {
+        Self {
+            regulator: Regulator::Main,
+            flash_power_down: false,
+            under_drive: false,
+            wait_for_event: false,
+        }
+    }
+
+    pub fn regulator(mut self, regulator: Regulator) -> Self {
+        self.regulator = regulator;
+        self
+    }
+
+    /// Powers the flash down during Stop, saves power but slows wake-up
+    pub fn flash_power_down(mut self, enabled: bool) -> Self {
+        self.flash_power_down = enabled;
+        self
+    }
+
+    /// Lowers the regulator voltage further, only possible while over-drive
+    /// is off
+    pub fn under_drive(mut self, enabled: bool) -> Self {
+        self.under_drive = enabled;
+        self
+    }
+
+    /// Enters Stop with WFE, for wake-up sources configured as EXTI events.
+    /// Events raised before [`PWR::enter_stop`] are discarded.
+    pub fn wait_for_event(mut self, enabled: bool) -> Self {
+        self.wait_for_event = enabled;
+        self
+    }
+}
+
+impl PWR {
+    /// Selects the regulator scale, takes effect the next time the PLL is
+    /// turned on, which also is the only time it may be changed
+    pub fn set_voltage_scale(&mut self, scale: VoltageScale) {
+        self.cr.set_voltage_scale(scale as u8);
+    }
+
+    pub fn get_voltage_scale(&self) -> VoltageScale {
+        match self.cr.get_voltage_scale() {
+            0b10 => VoltageScale::Scale2,
+            0b11 => VoltageScale::Scale1,
+            _ => VoltageScale::Scale3,
+        }
+    }
+
+    #[inline]
+    pub fn voltage_scale_is_ready(&self) -> bool {
+        self.csr.voltage_scale_is_ready()
+    }
+
+    /// Enables over-drive and switches the regulator to it.
+    ///
+    /// Needs voltage scale 1, with the PLL on and SYSCLK still on HSI or HSE.
+    pub fn enable_over_drive(&mut self) -> Result<()> {
+        self.cr.enable_over_drive();
+        wait_for(
+            READY_TIMEOUT,
+            || self.csr.over_drive_is_ready(),
+            Error::Timeout("over-drive ready")
+        )?;
+
+        self.cr.enable_over_drive_switching();
+        wait_for(
+            READY_TIMEOUT,
+            || self.csr.over_drive_switching_is_ready(),
+            Error::Timeout("over-drive switch")
+        )
+    }
+
+    /// Leaves over-drive, SYSCLK has to be at or below 168 MHz first
+    pub fn disable_over_drive(&mut self) {
+        self.cr.disable_over_drive_switching();
+        self.cr.disable_over_drive();
+    }
+
+    #[inline]
+    pub fn over_drive_is_enabled(&self) -> bool {
+        self.csr.over_drive_switching_is_ready()
+    }
+
+    /// Watches VDD against `level` and raises the PVD interrupt through EXTI
+    /// line 16 when it crosses it
+    pub fn enable_pvd(&mut self, level: PvdLevel, trigger: PvdTrigger) {
+        self.cr.set_pvd_level(level);
+        self.cr.enable_pvd();
+
+        let exti = EXTI::get();
+        match trigger {
+            PvdTrigger::Falling => {
+                exti.reset_rising_trigger_lines(Line::LINE16);
+                exti.set_falling_trigger_lines(Line::LINE16);
+            }
+            PvdTrigger::Rising => {
+                exti.set_rising_trigger_lines(Line::LINE16);
+                exti.reset_falling_trigger_lines(Line::LINE16);
+            }
+            PvdTrigger::Both => {
+                exti.set_rising_trigger_lines(Line::LINE16);
+                exti.set_falling_trigger_lines(Line::LINE16);
+            }
+        }
+        exti.clear_pending_lines(Line::LINE16);
+        exti.unmask_interrupts_lines(Line::LINE16);
+    }
+
+    pub fn disable_pvd(&mut self) {
+        EXTI::get().mask_interrupts_lines(Line::LINE16);
+
+        self.cr.disable_pvd();
+    }
+
+    /// Returns `true` while VDD is below the PVD threshold
+    #[inline]
+    pub fn vdd_is_below_pvd_threshold(&self) -> bool {
+        self.csr.is_vdd_below_pvd_threshold()
+    }
+
+    /// Clears the pending PVD interrupt, call it from the PVD handler
+    #[inline]
+    pub fn clear_pvd_pending(&mut self) {
+        EXTI::get().clear_pending_lines(Line::LINE16);
+    }
+
+    /// Allows writes to the RTC, backup registers and backup SRAM
+    #[inline]
+    pub fn enable_backup_access(&mut self) {
+        self.cr.enable_backup_access()
+    }
+
+    #[inline]
+    pub fn disable_backup_access(&mut self) {
+        self.cr.disable_backup_access()
+    }
+
+    #[inline]
+    pub fn backup_access_is_enabled(&self) -> bool {
+        self.cr.backup_access_is_enabled()
+    }
+
+    /// Keeps the backup SRAM powered from VBAT in Standby and VBAT modes
+    pub fn enable_backup_regulator(&mut self) -> Result<()> {
+        self.csr.enable_backup_regulator();
+        wait_for(
+            READY_TIMEOUT,
+            || self.csr.backup_regulator_is_ready(),
+            Error::Timeout("backup regulator")
+        )
+    }
+
+    #[inline]
+    pub fn disable_backup_regulator(&mut self) {
+        self.csr.disable_backup_regulator()
+    }
+
+    /// Enters Stop mode until one of the `wakeup` EXTI lines fires, then
+    /// brings the clock tree back to `clocks`.
+    ///
+    /// The wake-up lines have to be configured (trigger edges, GPIO source)
+    /// beforehand, their pending flags are cleared here so a stale one does
+    /// not end Stop right away. The core resumes on HSI, so `clocks` is
+    /// frozen again to restart HSE and the PLL.
+    ///
+    /// Under-drive is refused while over-drive is enabled, freeze a clock
+    /// tree without it first.
+    pub fn enter_stop(
+        &mut self,
+        wakeup: impl Into<LineMask>,
+        config: &StopConfig,
+        clocks: ClockConfig
+    ) -> Result<Clocks> {
+        self.enable_clock();
+
+        if config.under_drive && self.over_drive_is_enabled() {
+            return Err(Error::OverDriveEnabled);
+        }
+
+        let lines = wakeup.into();
+
+        let exti = EXTI::get();
+        exti.clear_pending_lines(lines);
+        if config.wait_for_event {
+            exti.unmask_events_lines(lines);
+        } else {
+            exti.unmask_interrupts_lines(lines);
+        }
+
+        let low_power = config.regulator == Regulator::LowPower;
+
+        self.cr.set_power_down_deep_sleep(false);
+        self.cr.set_low_power_deep_sleep(low_power);
+        self.cr.set_flash_power_down(config.flash_power_down);
+        if config.under_drive {
+            self.cr.set_under_drive(0b11);
+            self.cr.set_low_power_under_drive(low_power);
+            self.cr.set_main_under_drive(!low_power);
+        } else {
+            self.cr.set_under_drive(0b00);
+            self.cr.set_low_power_under_drive(false);
+            self.cr.set_main_under_drive(false);
+        }
+
+        if config.wait_for_event {
+            // a stale event would make WFE return without entering Stop, set
+            // the event register and consume it so only a new one wakes up
+            power::send_event();
+            asm::wfe();
+        }
+
+        power::set_sleep_mode(SleepMode::DeepSleep);
+        if config.wait_for_event {
+            power::wait_for_event();
+        } else {
+            power::wait_for_interrupt();
+        }
+        power::set_sleep_mode(SleepMode::Sleep);
+
+        if config.under_drive {
+            // UDRDY is cleared by writing ones
+            self.csr.set_under_drive_ready(0b11);
+        }
+
+        Ok(clocks.freeze()?)
+    }
+
+    /// Enters Standby mode, the device restarts from reset on wake-up.
+    ///
+    /// With `wakeup_pin` set a rising edge on PA0-WKUP wakes it up, the RTC
+    /// alarm, wake-up timer, tamper and time stamp events always do once they
+    /// are enabled in the RTC, whose flags have to be cleared before calling
+    /// this.
+    pub fn enter_standby(&mut self, wakeup_pin: bool) -> ! {
+        if wakeup_pin {
+            self.csr.enable_wakeup_pin();
+        } else {
+            self.csr.disable_wakeup_pin();
+        }
+
+        self.cr.set_clear_wakeup_flag(true);
+        self.cr.set_power_down_deep_sleep(true);
+
+        power::set_sleep_mode(SleepMode::DeepSleep);
+
+        loop {
+            power::wait_for_interrupt();
+        }
+    }
+
+    /// Returns `true` if the last reset was a wake-up from Standby
+    #[inline]
+    pub fn woke_from_standby(&self) -> bool {
+        self.csr.was_in_standby()
+    }
+
+    #[inline]
+    pub fn clear_standby_flag(&mut self) {
+        self.cr.set_clear_standby_flag(true);
+    }
+
+    /// Returns `true` if a wake-up event (WKUP pin or RTC) was received
+    #[inline]
+    pub fn wakeup_event_received(&self) -> bool {
+        self.csr.is_woken_up()
+    }
+
+    #[inline]
+    pub fn clear_wakeup_flag(&mut self) {
+        self.cr.set_clear_wakeup_flag(true);
+    }
+}
+
+impl PeripheralClock for PWR {
+    fn reset(&self) {
+        rcc().apb1rstr.pwr_reset(true);
+        rcc().apb1rstr.pwr_reset(false);
+    }
+
+    fn enable_clock(&self) {
+        rcc().apb1enr.pwr_enable();
+    }
+
+    fn disable_clock(&self) {
+        rcc().apb1enr.pwr_disable();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_voltage_scale_for_frequency() {
+        assert_eq!(VoltageScale::for_frequency(16_000_000), (VoltageScale::Scale3, false));
+        assert_eq!(VoltageScale::for_frequency(120_000_000), (VoltageScale::Scale3, false));
+        assert_eq!(VoltageScale::for_frequency(144_000_000), (VoltageScale::Scale2, false));
+        assert_eq!(VoltageScale::for_frequency(168_000_000), (VoltageScale::Scale1, false));
+        assert_eq!(VoltageScale::for_frequency(180_000_000), (VoltageScale::Scale1, true));
+    }
+}
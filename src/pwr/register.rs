@@ -0,0 +1,160 @@
+use register::{ field::derive::RegisterField, register };
+
+#[register(u32)]
+#[derive(Debug, Default)]
+pub struct PowerControlRegister {
+    #[bits(1, rw, get = low_power_deep_sleep_is_set, set = set_low_power_deep_sleep)]
+    pub LPDS: bool,
+
+    #[bits(1, rw, get = power_down_deep_sleep_is_set, set = set_power_down_deep_sleep)]
+    pub PDDS: bool,
+
+    #[bits(1, w, set = set_clear_wakeup_flag)]
+    pub CWUF: bool,
+
+    #[bits(1, w, set = set_clear_standby_flag)]
+    pub CSBF: bool,
+
+    #[bits(1, rwc, get = pvd_is_enabled, set = enable_pvd, clear = disable_pvd)]
+    pub PVDE: bool,
+
+    #[bits(3, rw, get = get_pvd_level, set = set_pvd_level)]
+    pub PLS: PvdLevel,
+
+    #[bits(
+        1,
+        rwc,
+        get = backup_access_is_enabled,
+        set = enable_backup_access,
+        clear = disable_backup_access
+    )]
+    pub DBP: bool,
+
+    #[bits(1, rw, get = flash_power_down_is_set, set = set_flash_power_down)]
+    pub FPDS: bool,
+
+    #[bits(1, rw, get = low_power_under_drive_is_set, set = set_low_power_under_drive)]
+    pub LPUDS: bool,
+
+    #[bits(1, rw, get = main_under_drive_is_set, set = set_main_under_drive)]
+    pub MRUDS: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, rw, get = adc_dc1_is_set, set = set_adc_dc1)]
+    pub ADCDC1: bool,
+
+    #[bits(2, rw, get = get_voltage_scale, set = set_voltage_scale)]
+    pub VOS: u8,
+
+    #[bits(
+        1,
+        rwc,
+        get = over_drive_is_enabled,
+        set = enable_over_drive,
+        clear = disable_over_drive
+    )]
+    pub ODEN: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = over_drive_switching_is_enabled,
+        set = enable_over_drive_switching,
+        clear = disable_over_drive_switching
+    )]
+    pub ODSWEN: bool,
+
+    #[bits(2, rw, get = get_under_drive, set = set_under_drive)]
+    pub UDEN: u8,
+
+    #[bits(12)]
+    __: u32,
+}
+
+#[register(u32)]
+#[derive(Debug, Default)]
+pub struct PowerControlAndStatusRegister {
+    #[bits(1, r, get = is_woken_up)]
+    pub WUF: bool,
+
+    #[bits(1, r, get = was_in_standby)]
+    pub SBF: bool,
+
+    #[bits(1, r, get = is_vdd_below_pvd_threshold)]
+    pub PVDO: bool,
+
+    #[bits(1, r, get = backup_regulator_is_ready)]
+    pub BRR: bool,
+
+    #[bits(4)]
+    __: u32,
+
+    #[bits(
+        1,
+        rwc,
+        get = wakeup_pin_is_enabled,
+        set = enable_wakeup_pin,
+        clear = disable_wakeup_pin
+    )]
+    pub EWUP: bool,
+
+    #[bits(
+        1,
+        rwc,
+        get = backup_regulator_is_enabled,
+        set = enable_backup_regulator,
+        clear = disable_backup_regulator
+    )]
+    pub BRE: bool,
+
+    #[bits(4)]
+    __: u32,
+
+    #[bits(1, r, get = voltage_scale_is_ready)]
+    pub VOSRDY: bool,
+
+    #[bits(1)]
+    __: u32,
+
+    #[bits(1, r, get = over_drive_is_ready)]
+    pub ODRDY: bool,
+
+    #[bits(1, r, get = over_drive_switching_is_ready)]
+    pub ODSWRDY: bool,
+
+    #[bits(2, rw, get = get_under_drive_ready, set = set_under_drive_ready)]
+    pub UDRDY: u8,
+
+    #[bits(12)]
+    __: u32,
+}
+
+/// Programmable voltage detector threshold
+#[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PvdLevel {
+    /// 2.0 V
+    Level0 = 0b000,
+
+    /// 2.1 V
+    Level1 = 0b001,
+
+    /// 2.3 V
+    Level2 = 0b010,
+
+    /// 2.5 V
+    Level3 = 0b011,
+
+    /// 2.6 V
+    Level4 = 0b100,
+
+    /// 2.7 V
+    Level5 = 0b101,
+
+    /// 2.8 V
+    Level6 = 0b110,
+
+    /// 2.9 V
+    Level7 = 0b111,
+}
@@ -0,0 +1,509 @@
+//! PLLI2S and PLLSAI, the auxiliary PLLs clocking I2S, SAI and the LCD-TFT
+//! controller.
+//!
+//! Both share the source and the M divider of the main PLL, so the solvers
+//! take the resulting PLL input frequency and only pick N and the output
+//! dividers.
+
+use crate::{ time::Hertz, wait_for, State, READY_TIMEOUT };
+
+use super::{
+    clocks::{
+        Error,
+        Result,
+        HSI_FREQ,
+        PLLN_MAX,
+        PLLN_MIN,
+        PLLQ_MAX,
+        PLLQ_MIN,
+        VCO_MAX,
+        VCO_MIN,
+    },
+    external_osc_freq,
+    I2SClockSource,
+    LCDClockDivisionFactor,
+    PLLClockSource,
+    SAIClockSource,
+    RCC,
+};
+
+const PLLR_MIN: u32 = 2;
+const PLLR_MAX: u32 = 7;
+const PLLDIVQ_MAX: u32 = 32;
+
+/// Highest clock accepted by the I2S prescaler
+const I2S_CLK_MAX: u32 = 192_000_000;
+
+/// Range of the I2S linear prescaler, 2 * I2SDIV + ODD
+const I2S_DIV_MIN: u32 = 4;
+const I2S_DIV_MAX: u32 = 511;
+
+const LCD_DIVIDERS: [(u32, LCDClockDivisionFactor); 4] = [
+    (2, LCDClockDivisionFactor::DividedBy2),
+    (4, LCDClockDivisionFactor::DividedBy4),
+    (8, LCDClockDivisionFactor::DividedBy8),
+    (16, LCDClockDivisionFactor::DividedBy16),
+];
+
+/// Relative error of `actual` against `target` in parts per billion
+fn error_ppb(actual: u64, target: u64) -> u64 {
+    (actual.abs_diff(target) as u128 * 1_000_000_000 / target as u128) as u64
+}
+
+/// Q and DIVQ bringing `vco` closest to the SAI clock `target`, with the
+/// error in parts per billion
+fn sai_dividers(vco: u64, target: u32) -> (u64, u32, u32) {
+    let mut best = (u64::MAX, PLLQ_MIN, 1);
+
+    for q in PLLQ_MIN..=PLLQ_MAX {
+        for divq in 1..=PLLDIVQ_MAX {
+            let error = error_ppb(vco, target as u64 * (q * divq) as u64);
+            if error < best.0 {
+                best = (error, q, divq);
+            }
+        }
+    }
+
+    best
+}
+
+/// I2S sample rate builder, optionally with a SAI clock from the same PLL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct I2sConfig {
+    sample_rate: u32,
+    master_clock: bool,
+    channel_32bit: bool,
+    sai: Option<Hertz>,
+}
+
+/// PLLI2S factors and I2S prescaler reaching a sample rate, I2S = VCO / R
+/// and SAI = VCO / Q / DIVQ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct I2sPlan {
+    pub n: u32,
+    pub r: u32,
+    pub q: u32,
+    pub divq: u32,
+
+    /// Value of the SPI_I2SPR I2SDIV field
+    pub i2s_div: u8,
+
+    /// Value of the SPI_I2SPR ODD bit
+    pub odd: bool,
+
+    pub i2s_clk: Hertz,
+
+    /// Sample rate actually reached
+    pub sample_rate: u32,
+
+    /// SAI clock reached, `None` if it was not requested
+    pub sai_clk: Option<Hertz>,
+}
+
+impl I2sConfig {
+    /// 16-bit channels, MCLK not output
+    pub const fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            master_clock: false,
+            channel_32bit: false,
+            sai: None,
+        }
+    }
+
+    /// Outputs MCLK at 256 times the sample rate
+    pub fn master_clock(mut self, enabled: bool) -> Self {
+        self.master_clock = enabled;
+        self
+    }
+
+    /// 32-bit channels, only changes the clocking while MCLK is off
+    pub fn channel_32bit(mut self, enabled: bool) -> Self {
+        self.channel_32bit = enabled;
+        self
+    }
+
+    /// Target SAI clock, fed to SAI1 block B
+    pub fn sai(mut self, freq: Hertz) -> Self {
+        self.sai = Some(freq);
+        self
+    }
+
+    /// I2S clock cycles per sample
+    fn cycles_per_sample(&self) -> u32 {
+        if self.master_clock {
+            256
+        } else if self.channel_32bit {
+            64
+        } else {
+            32
+        }
+    }
+
+    /// Finds the PLLI2S factors and I2S prescaler giving the sample rate
+    /// closest to the requested one, minimizing the summed relative error
+    /// with the SAI clock if there is one. `pll_input` is the main PLL source
+    /// divided by M.
+    pub fn solve(&self, pll_input: u32) -> Result<I2sPlan> {
+        let target = self.sample_rate as u64 * self.cycles_per_sample() as u64;
+        if target == 0 {
+            return Err(Error::SampleRateUnreachable(self.sample_rate));
+        }
+        if self.sai.map_or(false, |f| f.raw() == 0) {
+            return Err(Error::SaiClockUnreachable(0));
+        }
+
+        let mut best: Option<(u64, I2sPlan)> = None;
+
+        for n in PLLN_MIN..=PLLN_MAX {
+            let vco = pll_input as u64 * n as u64;
+            if vco < VCO_MIN as u64 || vco > VCO_MAX as u64 {
+                continue;
+            }
+
+            // unused SAI output keeps the smallest valid dividers
+            let (sai_error, q, divq) = match self.sai {
+                Some(Hertz(sai)) => sai_dividers(vco, sai),
+                None => (0, PLLQ_MIN, 1),
+            };
+
+            for r in PLLR_MIN..=PLLR_MAX {
+                let i2s_clk = vco / r as u64;
+                if i2s_clk > I2S_CLK_MAX as u64 {
+                    continue;
+                }
+
+                let div = ((i2s_clk + target / 2) / target)
+                    .clamp(I2S_DIV_MIN as u64, I2S_DIV_MAX as u64);
+                let error = error_ppb(i2s_clk, target * div) + sai_error;
+
+                if best.map_or(true, |(e, _)| error < e) {
+                    let sample_rate = i2s_clk / (div * self.cycles_per_sample() as u64);
+
+                    best = Some((error, I2sPlan {
+                        n,
+                        r,
+                        q,
+                        divq,
+                        i2s_div: (div / 2) as u8,
+                        odd: div & 1 == 1,
+                        i2s_clk: Hertz(i2s_clk as u32),
+                        sample_rate: sample_rate as u32,
+                        sai_clk: self.sai.map(|_| Hertz((vco / (q * divq) as u64) as u32)),
+                    }));
+                }
+            }
+        }
+
+        best.map(|(_, plan)| plan).ok_or(Error::SampleRateUnreachable(self.sample_rate))
+    }
+}
+
+/// SAI and LCD-TFT clock builder, both come from PLLSAI and share its VCO
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PllSaiConfig {
+    sai: Option<Hertz>,
+    lcd: Option<Hertz>,
+}
+
+/// PLLSAI factors, SAI = VCO / Q / DIVQ and LCD = VCO / R / DIVR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PllSaiPlan {
+    pub n: u32,
+    pub q: u32,
+    pub divq: u32,
+    pub r: u32,
+    pub divr: LCDClockDivisionFactor,
+
+    /// SAI clock reached, `None` if it was not requested
+    pub sai_clk: Option<Hertz>,
+
+    /// LCD-TFT clock reached, `None` if it was not requested
+    pub lcd_clk: Option<Hertz>,
+}
+
+impl PllSaiConfig {
+    pub const fn new() -> Self {
+        Self { sai: None, lcd: None }
+    }
+
+    /// Target SAI clock, fed to SAI1 block A
+    pub fn sai(mut self, freq: Hertz) -> Self {
+        self.sai = Some(freq);
+        self
+    }
+
+    /// Target LCD-TFT controller pixel clock
+    pub fn lcd(mut self, freq: Hertz) -> Self {
+        self.lcd = Some(freq);
+        self
+    }
+
+    /// Finds the PLLSAI factors minimizing the summed relative error of both
+    /// outputs, `pll_input` being the main PLL source divided by M
+    pub fn solve(&self, pll_input: u32) -> Result<PllSaiPlan> {
+        let requested = self.sai.or(self.lcd).ok_or(Error::SaiClockUnreachable(0))?;
+        if self.sai.map_or(false, |f| f.raw() == 0) || self.lcd.map_or(false, |f| f.raw() == 0) {
+            return Err(Error::SaiClockUnreachable(0));
+        }
+
+        let mut best: Option<(u64, PllSaiPlan)> = None;
+
+        for n in PLLN_MIN..=PLLN_MAX {
+            let vco = pll_input as u64 * n as u64;
+            if vco < VCO_MIN as u64 || vco > VCO_MAX as u64 {
+                continue;
+            }
+
+            // unused outputs keep the smallest valid dividers
+            let mut plan = PllSaiPlan {
+                n,
+                q: PLLQ_MIN,
+                divq: 1,
+                r: PLLR_MIN,
+                divr: LCDClockDivisionFactor::DividedBy2,
+                sai_clk: None,
+                lcd_clk: None,
+            };
+            let mut error = 0;
+
+            if let Some(Hertz(target)) = self.sai {
+                let (e, q, divq) = sai_dividers(vco, target);
+
+                error += e;
+                plan.q = q;
+                plan.divq = divq;
+                plan.sai_clk = Some(Hertz((vco / (q * divq) as u64) as u32));
+            }
+
+            if let Some(Hertz(target)) = self.lcd {
+                let mut lcd_best: Option<(u64, u32, u32, LCDClockDivisionFactor)> = None;
+
+                for r in PLLR_MIN..=PLLR_MAX {
+                    for &(divr_value, divr) in LCD_DIVIDERS.iter() {
+                        let e = error_ppb(vco, target as u64 * (r * divr_value) as u64);
+                        if lcd_best.map_or(true, |(best, _, _, _)| e < best) {
+                            lcd_best = Some((e, r, divr_value, divr));
+                        }
+                    }
+                }
+
+                if let Some((e, r, divr_value, divr)) = lcd_best {
+                    error += e;
+                    plan.r = r;
+                    plan.divr = divr;
+                    plan.lcd_clk = Some(Hertz((vco / (r * divr_value) as u64) as u32));
+                }
+            }
+
+            if best.map_or(true, |(e, _)| error < e) {
+                best = Some((error, plan));
+            }
+        }
+
+        best.map(|(_, plan)| plan).ok_or(Error::SaiClockUnreachable(requested.raw()))
+    }
+}
+
+impl RCC {
+    /// Input of the three PLLs, the main PLL source divided by M
+    pub fn pll_input_freq(&self) -> u32 {
+        let source = match self.pll_clock_source() {
+            PLLClockSource::HSI => HSI_FREQ,
+            PLLClockSource::HSE => external_osc_freq(),
+        };
+
+        source / self.pllcfgr.pll_get_division_factor()
+    }
+
+    /// Programs PLLI2S, selects it as the I2S clock and waits for the lock,
+    /// SAI1 block B is switched to it if a SAI clock was planned
+    pub fn configure_plli2s(&mut self, plan: &I2sPlan) -> Result<()> {
+        self.cr.pll_i2s_set(State::OFF);
+        wait_for(READY_TIMEOUT, || !self.cr.pll_i2s_is_ready(), Error::Timeout("PLLI2S stop"))?;
+
+        self.plli2scfgr.pll_i2s_vco_set_multiplication_factor(plan.n as u16);
+        self.plli2scfgr.pll_i2s_sai1_set_division_factor(plan.q as u8);
+        self.plli2scfgr.pll_i2s_i2s_set_division_factor(plan.r as u8);
+        self.dckcfgr.pll_i2s_sai_set_division_factor((plan.divq - 1) as u8);
+        self.cfgr.i2s_set_clock_source(I2SClockSource::PLLI2S);
+
+        if plan.sai_clk.is_some() {
+            self.dckcfgr.sai1b_set_clock_source(SAIClockSource::PLLI2S_PLLI2SDIV);
+        }
+
+        self.cr.pll_i2s_set(State::ON);
+        wait_for(READY_TIMEOUT, || self.cr.pll_i2s_is_ready(), Error::Timeout("PLLI2S ready"))
+    }
+
+    /// Programs PLLSAI and waits for the lock, SAI1 block A is switched to it
+    /// if a SAI clock was planned
+    pub fn configure_pllsai(&mut self, plan: &PllSaiPlan) -> Result<()> {
+        self.cr.pll_sai_set(State::OFF);
+        wait_for(READY_TIMEOUT, || !self.cr.pll_sai_is_ready(), Error::Timeout("PLLSAI stop"))?;
+
+        self.pllsaicfgr.pll_sai_vco_set_multiplication_factor(plan.n as u16);
+        self.pllsaicfgr.pll_sai_sai1_set_division_factor(plan.q as u8);
+        self.pllsaicfgr.pll_sai_lcd_set_division_factor(plan.r as u8);
+        self.dckcfgr.pll_sai_sai1_set_division_factor((plan.divq - 1) as u8);
+        self.dckcfgr.pll_sai_lcd_set_division_factor(plan.divr);
+
+        if plan.sai_clk.is_some() {
+            self.dckcfgr.sai1a_set_clock_source(SAIClockSource::PLLSAI_PLLSAIDIV);
+        }
+
+        self.cr.pll_sai_set(State::ON);
+        wait_for(READY_TIMEOUT, || self.cr.pll_sai_is_ready(), Error::Timeout("PLLSAI ready"))
+    }
+
+    fn plli2s_vco_freq(&self) -> u32 {
+        self.pll_input_freq() * self.plli2scfgr.pll_i2s_vco_get_multiplication_factor() as u32
+    }
+
+    fn pllsai_vco_freq(&self) -> u32 {
+        self.pll_input_freq() * self.pllsaicfgr.pll_sai_vco_get_multiplication_factor() as u32
+    }
+
+    /// I2S clock, 0 when it comes from the I2S_CKIN pin
+    pub fn i2s_clk_freq(&self) -> u32 {
+        match self.cfgr.i2s_get_clock_source() {
+            I2SClockSource::PLLI2S =>
+                self.plli2s_vco_freq() / self.plli2scfgr.pll_i2s_i2s_get_division_factor() as u32,
+            I2SClockSource::External => 0,
+        }
+    }
+
+    fn sai_clk_freq(&self, source: SAIClockSource) -> u32 {
+        match source {
+            SAIClockSource::PLLSAI_PLLSAIDIV => {
+                let q = self.pllsaicfgr.pll_sai_sai1_get_division_factor() as u32;
+                let divq = self.dckcfgr.pll_sai_sai1_get_division_factor() as u32 + 1;
+
+                self.pllsai_vco_freq() / (q * divq)
+            }
+            SAIClockSource::PLLI2S_PLLI2SDIV => {
+                let q = self.plli2scfgr.pll_i2s_sai1_get_division_factor() as u32;
+                let divq = self.dckcfgr.pll_i2s_sai_get_division_factor() as u32 + 1;
+
+                self.plli2s_vco_freq() / (q * divq)
+            }
+            SAIClockSource::Alternate => 0,
+        }
+    }
+
+    /// SAI1 block A clock, 0 when it comes from the I2S_CKIN pin
+    #[inline]
+    pub fn sai_a_clk_freq(&self) -> u32 {
+        self.sai_clk_freq(self.dckcfgr.sai1a_get_clock_source())
+    }
+
+    /// SAI1 block B clock, 0 when it comes from the I2S_CKIN pin
+    #[inline]
+    pub fn sai_b_clk_freq(&self) -> u32 {
+        self.sai_clk_freq(self.dckcfgr.sai1b_get_clock_source())
+    }
+
+    /// LCD-TFT controller clock
+    pub fn lcd_clk_freq(&self) -> u32 {
+        let r = self.pllsaicfgr.pll_sai_lcd_get_division_factor() as u32;
+        let divr = match self.dckcfgr.pll_sai_lcd_get_division_factor() {
+            LCDClockDivisionFactor::DividedBy2 => 2,
+            LCDClockDivisionFactor::DividedBy4 => 4,
+            LCDClockDivisionFactor::DividedBy8 => 8,
+            LCDClockDivisionFactor::DividedBy16 => 16,
+        };
+
+        self.pllsai_vco_freq() / (r * divr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::time::U32Ext;
+
+    use super::*;
+
+    #[test]
+    fn test_i2s_sample_rates() {
+        for rate in [8_000u32, 16_000, 22_050, 32_000, 44_100, 48_000, 96_000] {
+            for mclk in [false, true] {
+                let plan = I2sConfig::new(rate).master_clock(mclk).solve(1_000_000).unwrap();
+
+                let vco = 1_000_000 * plan.n;
+                assert!(vco >= VCO_MIN && vco <= VCO_MAX);
+                assert!(plan.r >= PLLR_MIN && plan.r <= PLLR_MAX);
+                assert!(plan.i2s_div >= 2);
+
+                let div = 2 * plan.i2s_div as u32 + plan.odd as u32;
+                let cycles = if mclk { 256 } else { 32 };
+                assert_eq!(plan.i2s_clk.raw(), vco / plan.r);
+                assert_eq!(plan.sample_rate, plan.i2s_clk.raw() / (div * cycles));
+
+                // within 0.05 %
+                assert!(plan.sample_rate.abs_diff(rate) * 2000 <= rate, "{} Hz", rate);
+            }
+        }
+    }
+
+    #[test]
+    fn test_plli2s_with_sai() {
+        let plan = I2sConfig::new(48_000).sai(Hertz(12_288_000)).solve(1_000_000).unwrap();
+        let vco = 1_000_000 * plan.n;
+        assert!(plan.q >= PLLQ_MIN && plan.q <= PLLQ_MAX);
+        assert!(plan.divq >= 1 && plan.divq <= PLLDIVQ_MAX);
+        assert_eq!(plan.sai_clk, Some(Hertz(vco / (plan.q * plan.divq))));
+
+        // both within 0.05 % from the shared VCO
+        assert!(plan.sample_rate.abs_diff(48_000) * 2000 <= 48_000);
+        assert!(plan.sai_clk.unwrap().raw().abs_diff(12_288_000) * 2000 <= 12_288_000);
+
+        let plan = I2sConfig::new(48_000).solve(1_000_000).unwrap();
+        assert_eq!((plan.q, plan.divq, plan.sai_clk), (PLLQ_MIN, 1, None));
+
+        assert_eq!(
+            I2sConfig::new(48_000).sai(Hertz(0)).solve(1_000_000),
+            Err(Error::SaiClockUnreachable(0))
+        );
+    }
+
+    #[test]
+    fn test_pllsai_lcd_and_sai() {
+        // 480x272 panel, exact from a 1 MHz PLL input
+        let plan = PllSaiConfig::new().lcd(9.MHz()).solve(1_000_000).unwrap();
+        assert_eq!(plan.lcd_clk, Some(9.MHz()));
+        assert_eq!(plan.sai_clk, None);
+
+        // 48 kHz * 1024 on SAI
+        let plan = PllSaiConfig::new().sai(Hertz(49_152_000)).solve(1_000_000).unwrap();
+        let vco = 1_000_000 * plan.n;
+        assert!(vco >= VCO_MIN && vco <= VCO_MAX);
+        assert!(plan.q >= PLLQ_MIN && plan.q <= PLLQ_MAX);
+        assert!(plan.divq >= 1 && plan.divq <= PLLDIVQ_MAX);
+        assert!(plan.sai_clk.unwrap().raw().abs_diff(49_152_000) * 2000 <= 49_152_000);
+        assert_eq!(plan.lcd_clk, None);
+
+        // both outputs share the VCO
+        let plan = PllSaiConfig::new().sai(48.MHz()).lcd(9.MHz()).solve(2_000_000).unwrap();
+        assert_eq!(plan.sai_clk, Some(48.MHz()));
+        assert_eq!(plan.lcd_clk, Some(9.MHz()));
+
+        assert_eq!(PllSaiConfig::new().solve(1_000_000), Err(Error::SaiClockUnreachable(0)));
+    }
+
+    #[test]
+    fn test_auxiliary_clock_read_back() {
+        let mut rcc = RCC::default();
+
+        // HSI / 16, PLLI2S N = 258, R = 3, Q = 4
+        rcc.pllcfgr.set(16);
+        rcc.plli2scfgr.set((258 << 6) | (4 << 24) | (3 << 28));
+        assert_eq!(rcc.i2s_clk_freq(), 86_000_000);
+
+        // PLLSAI N = 216, Q = 4, R = 6, DIVQ = 2, DIVR = 4, SAI1B from PLLI2S
+        rcc.pllsaicfgr.set((216 << 6) | (4 << 24) | (6 << 28));
+        rcc.dckcfgr.set((1 << 8) | (0b01 << 16) | (0b01 << 22));
+        assert_eq!(rcc.lcd_clk_freq(), 9_000_000);
+        assert_eq!(rcc.sai_a_clk_freq(), 27_000_000);
+        assert_eq!(rcc.sai_b_clk_freq(), 64_500_000);
+    }
+}
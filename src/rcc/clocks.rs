@@ -0,0 +1,871 @@
+//! Clock tree configuration.
+//!
+//! [`ClockConfig`] describes the wanted frequencies, [`ClockConfig::solve`]
+//! turns them into PLL factors and bus prescalers without touching hardware
+//! and [`ClockConfig::freeze`] programs the result.
+
+use core::fmt;
+
+use crate::{
+    flash::{ flash, wait_states, VoltageRange },
+    pwr::{ pwr, VoltageScale },
+    time::Hertz,
+    wait_for,
+    PeripheralClock,
+    State,
+    READY_TIMEOUT,
+};
+
+use super::{
+    external_osc_freq,
+    rcc,
+    set_external_osc_freq,
+    timer_clock_freq,
+    AHBPrescaler,
+    APBPrescaler,
+    PLLClockSource,
+    PLLSysClockDivisionFactor,
+    RTCClockSource,
+    SpreadSpectrum,
+    SpreadSpectrumSettings,
+    SystemClockSource,
+    TimerPrescaler,
+    RCC,
+};
+
+/// Internal high-speed oscillator frequency
+pub const HSI_FREQ: u32 = 16_000_000;
+
+const HSE_MIN: u32 = 4_000_000;
+const HSE_MAX: u32 = 26_000_000;
+const HSE_BYPASS_MIN: u32 = 1_000_000;
+const HSE_BYPASS_MAX: u32 = 50_000_000;
+
+const PLL_IN_MIN: u32 = 1_000_000;
+const PLL_IN_MAX: u32 = 2_000_000;
+pub(super) const VCO_MIN: u32 = 100_000_000;
+pub(super) const VCO_MAX: u32 = 432_000_000;
+const PLLM_MIN: u32 = 2;
+const PLLM_MAX: u32 = 63;
+pub(super) const PLLN_MIN: u32 = 50;
+pub(super) const PLLN_MAX: u32 = 432;
+pub(super) const PLLQ_MIN: u32 = 2;
+pub(super) const PLLQ_MAX: u32 = 15;
+
+/// Frequency needed by USB OTG FS, SDIO and RNG
+const PLL48_FREQ: u32 = 48_000_000;
+
+/// Highest SYSCLK, reached with over-drive enabled
+pub const SYSCLK_MAX: u32 = 180_000_000;
+pub const PCLK1_MAX: u32 = 45_000_000;
+pub const PCLK2_MAX: u32 = 90_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// HSE frequency is outside of the supported range
+    InvalidHseFrequency(u32),
+
+    /// Requested SYSCLK is above the device limit
+    SysclkOutOfRange(u32),
+
+    /// No PLL configuration gets close to the requested SYSCLK
+    SysclkUnreachable(u32),
+
+    /// No PLL configuration produces exactly 48 MHz on PLL48CLK
+    Pll48Unreachable,
+
+    /// Requested bus clock is above its limit or cannot be reached with
+    /// the available prescalers
+    BusClockOutOfRange(u32),
+
+    /// No PLLI2S configuration gets close to the requested sample rate
+    SampleRateUnreachable(u32),
+
+    /// No PLLSAI configuration gets close to the requested SAI or LCD clock
+    SaiClockUnreachable(u32),
+
+    /// Spread-spectrum modulation frequency or depth is outside of what the
+    /// SSCG register fields can hold
+    SpreadSpectrumOutOfRange,
+
+    /// PLL has to be stopped for this change
+    PllRunning,
+
+    /// PLLI2S or PLLSAI runs and would see its input change with the main
+    /// PLL source or M divider
+    AuxiliaryPllRunning,
+
+    /// RTCSEL already selects another clock and a backup domain reset is
+    /// not allowed
+    RtcSourceLocked(RTCClockSource),
+
+    /// HSE has to be started before it can clock the RTC
+    HseNotRunning,
+
+    /// An oscillator or clock switch did not become ready in time
+    Timeout(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidHseFrequency(freq) =>
+                f.write_fmt(format_args!("Invalid HSE frequency: {}", freq)),
+            Error::SysclkOutOfRange(freq) =>
+                f.write_fmt(format_args!("SYSCLK out of range: {}", freq)),
+            Error::SysclkUnreachable(freq) =>
+                f.write_fmt(format_args!("SYSCLK unreachable: {}", freq)),
+            Error::Pll48Unreachable => f.write_str("PLL48CLK unreachable"),
+            Error::BusClockOutOfRange(freq) =>
+                f.write_fmt(format_args!("Bus clock out of range: {}", freq)),
+            Error::SampleRateUnreachable(rate) =>
+                f.write_fmt(format_args!("Sample rate unreachable: {}", rate)),
+            Error::SaiClockUnreachable(freq) =>
+                f.write_fmt(format_args!("SAI/LCD clock unreachable: {}", freq)),
+            Error::SpreadSpectrumOutOfRange => f.write_str("Spread spectrum out of range"),
+            Error::PllRunning => f.write_str("PLL running"),
+            Error::AuxiliaryPllRunning => f.write_str("PLLI2S or PLLSAI running"),
+            Error::RtcSourceLocked(source) =>
+                f.write_fmt(format_args!("RTC clock already set to {:?}", source)),
+            Error::HseNotRunning => f.write_str("HSE not running"),
+            Error::Timeout(e) => f.write_fmt(format_args!("Timeout: {}", e)),
+        }
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Main PLL factors, VCO = input / M * N, SYSCLK = VCO / P, PLL48CLK = VCO / Q
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PllConfig {
+    pub source: PLLClockSource,
+    pub m: u32,
+    pub n: u32,
+    pub p: PLLSysClockDivisionFactor,
+    pub q: u32,
+}
+
+impl PllConfig {
+    fn p_value(&self) -> u32 {
+        match self.p {
+            PLLSysClockDivisionFactor::DividedBy2 => 2,
+            PLLSysClockDivisionFactor::DividedBy4 => 4,
+            PLLSysClockDivisionFactor::DividedBy6 => 6,
+            PLLSysClockDivisionFactor::DividedBy8 => 8,
+        }
+    }
+
+    fn vco_freq(&self, input: u32) -> u32 {
+        ((input as u64 * self.n as u64) / self.m as u64) as u32
+    }
+
+    pub fn sysclk_freq(&self, input: u32) -> u32 {
+        self.vco_freq(input) / self.p_value()
+    }
+
+    pub fn pll48_freq(&self, input: u32) -> u32 {
+        self.vco_freq(input) / self.q
+    }
+}
+
+/// Frozen clock frequencies, proof that the clock tree has been configured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clocks {
+    hse: Option<Hertz>,
+    sysclk: Hertz,
+    hclk: Hertz,
+    pclk1: Hertz,
+    pclk2: Hertz,
+    timclk1: Hertz,
+    timclk2: Hertz,
+    pll48clk: Option<Hertz>,
+}
+
+impl Clocks {
+    #[inline]
+    pub fn hse(&self) -> Option<Hertz> {
+        self.hse
+    }
+
+    #[inline]
+    pub fn sysclk(&self) -> Hertz {
+        self.sysclk
+    }
+
+    #[inline]
+    pub fn hclk(&self) -> Hertz {
+        self.hclk
+    }
+
+    #[inline]
+    pub fn pclk1(&self) -> Hertz {
+        self.pclk1
+    }
+
+    #[inline]
+    pub fn pclk2(&self) -> Hertz {
+        self.pclk2
+    }
+
+    /// Kernel clock of the timers on APB1
+    #[inline]
+    pub fn timclk1(&self) -> Hertz {
+        self.timclk1
+    }
+
+    /// Kernel clock of the timers on APB2
+    #[inline]
+    pub fn timclk2(&self) -> Hertz {
+        self.timclk2
+    }
+
+    /// Clock of USB OTG FS, SDIO and RNG, `None` if the PLL is not used
+    #[inline]
+    pub fn pll48clk(&self) -> Option<Hertz> {
+        self.pll48clk
+    }
+}
+
+impl RCC {
+    /// Reads back the frequencies the clock tree is currently running at
+    pub fn clocks(&self) -> Clocks {
+        let hse = if self.cr.hse_is_ready() { Some(Hertz(external_osc_freq())) } else { None };
+
+        let pll48clk = if self.cr.pll_is_ready() {
+            let input = match self.pll_clock_source() {
+                PLLClockSource::HSI => HSI_FREQ,
+                PLLClockSource::HSE => external_osc_freq(),
+            };
+            let pll = PllConfig {
+                source: self.pll_clock_source(),
+                m: self.pllcfgr.pll_get_division_factor(),
+                n: self.pllcfgr.pll_get_mutiplication_factor(),
+                p: self.pllcfgr.pll_get_sysclock_division_factor(),
+                q: self.pllcfgr.pll_get_usb_sdio_rng_division_factor(),
+            };
+
+            Some(Hertz(pll.pll48_freq(input)))
+        } else {
+            None
+        };
+
+        Clocks {
+            hse,
+            sysclk: Hertz(self.sysclk_freq()),
+            hclk: Hertz(self.hclk_freq()),
+            pclk1: Hertz(self.pclk1_freq()),
+            pclk2: Hertz(self.pclk2_freq()),
+            timclk1: Hertz(self.timclk1_freq()),
+            timclk2: Hertz(self.timclk2_freq()),
+            pll48clk,
+        }
+    }
+}
+
+/// Register values computed by [`ClockConfig::solve`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockPlan {
+    pub sysclk_source: SystemClockSource,
+    pub pll: Option<PllConfig>,
+    pub hpre: AHBPrescaler,
+    pub ppre1: APBPrescaler,
+    pub ppre2: APBPrescaler,
+    pub flash_latency: u8,
+    pub voltage_scale: VoltageScale,
+    pub over_drive: bool,
+    pub spread_spectrum: Option<SpreadSpectrumSettings>,
+    pub clocks: Clocks,
+}
+
+/// Clock tree configuration builder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockConfig {
+    hse: Option<Hertz>,
+    hse_bypass: bool,
+    sysclk: Option<Hertz>,
+    hclk: Option<Hertz>,
+    pclk1: Option<Hertz>,
+    pclk2: Option<Hertz>,
+    pll48clk: bool,
+    spread_spectrum: Option<SpreadSpectrum>,
+    voltage_range: VoltageRange,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockConfig {
+    /// Runs everything from HSI at 16 MHz until told otherwise
+    pub const fn new() -> Self {
+        Self {
+            hse: None,
+            hse_bypass: false,
+            sysclk: None,
+            hclk: None,
+            pclk1: None,
+            pclk2: None,
+            pll48clk: false,
+            spread_spectrum: None,
+            voltage_range: VoltageRange::V2_7To3_6,
+        }
+    }
+
+    /// Uses a crystal of `freq` on OSC_IN/OSC_OUT instead of HSI
+    pub fn hse(mut self, freq: Hertz) -> Self {
+        self.hse = Some(freq);
+        self.hse_bypass = false;
+        self
+    }
+
+    /// Uses an external clock of `freq` fed into OSC_IN instead of HSI
+    pub fn hse_bypass(mut self, freq: Hertz) -> Self {
+        self.hse = Some(freq);
+        self.hse_bypass = true;
+        self
+    }
+
+    /// Same targets with HSI as the only source, used to rebuild the clock
+    /// tree after a HSE failure
+    pub fn without_hse(mut self) -> Self {
+        self.hse = None;
+        self.hse_bypass = false;
+        self
+    }
+
+    /// Target SYSCLK, the PLL is used if it differs from the oscillator
+    pub fn sysclk(mut self, freq: Hertz) -> Self {
+        self.sysclk = Some(freq);
+        self
+    }
+
+    /// Upper bound for HCLK, defaults to SYSCLK
+    pub fn hclk(mut self, freq: Hertz) -> Self {
+        self.hclk = Some(freq);
+        self
+    }
+
+    /// Upper bound for PCLK1, defaults to the highest allowed value
+    pub fn pclk1(mut self, freq: Hertz) -> Self {
+        self.pclk1 = Some(freq);
+        self
+    }
+
+    /// Upper bound for PCLK2, defaults to the highest allowed value
+    pub fn pclk2(mut self, freq: Hertz) -> Self {
+        self.pclk2 = Some(freq);
+        self
+    }
+
+    /// Requires exactly 48 MHz on PLL48CLK, as needed by USB OTG FS
+    pub fn require_pll48clk(mut self) -> Self {
+        self.pll48clk = true;
+        self
+    }
+
+    /// Modulates the main PLL, which is then always used for SYSCLK
+    pub fn spread_spectrum(mut self, spread_spectrum: SpreadSpectrum) -> Self {
+        self.spread_spectrum = Some(spread_spectrum);
+        self
+    }
+
+    /// Supply voltage, used to select the flash wait states
+    pub fn voltage_range(mut self, range: VoltageRange) -> Self {
+        self.voltage_range = range;
+        self
+    }
+
+    fn source_freq(&self) -> Result<u32> {
+        match self.hse {
+            Some(Hertz(freq)) => {
+                let (min, max) = if self.hse_bypass {
+                    (HSE_BYPASS_MIN, HSE_BYPASS_MAX)
+                } else {
+                    (HSE_MIN, HSE_MAX)
+                };
+
+                if freq < min || freq > max {
+                    return Err(Error::InvalidHseFrequency(freq));
+                }
+
+                Ok(freq)
+            }
+            None => Ok(HSI_FREQ),
+        }
+    }
+
+    /// Computes PLL factors, prescalers and flash latency, hardware is not
+    /// touched
+    pub fn solve(&self) -> Result<ClockPlan> {
+        let input = self.source_freq()?;
+
+        let target = self.sysclk.map_or(input, |f| f.raw());
+        if target == 0 || target > SYSCLK_MAX {
+            return Err(Error::SysclkOutOfRange(target));
+        }
+
+        let needs_pll = self.pll48clk || self.spread_spectrum.is_some();
+        let (sysclk_source, pll, sysclk) = if target == input && !needs_pll {
+            let source = if self.hse.is_some() {
+                SystemClockSource::HSE
+            } else {
+                SystemClockSource::HSI
+            };
+
+            (source, None, input)
+        } else {
+            let source = if self.hse.is_some() { PLLClockSource::HSE } else { PLLClockSource::HSI };
+            let pll = solve_pll(source, input, target, self.pll48clk)?;
+
+            (SystemClockSource::PLL, Some(pll), pll.sysclk_freq(input))
+        };
+
+        let spread_spectrum = match (self.spread_spectrum, pll) {
+            (Some(ss), Some(pll)) => Some(ss.solve(input / pll.m, pll.n)?),
+            _ => None,
+        };
+
+        let hclk_max = self.hclk.map_or(sysclk, |f| f.raw());
+        let (hpre, hclk) = ahb_prescaler(sysclk, hclk_max)?;
+
+        let pclk1_max = bus_limit(self.pclk1, hclk, PCLK1_MAX)?;
+        let (ppre1, pclk1) = apb_prescaler(hclk, pclk1_max)?;
+
+        let pclk2_max = bus_limit(self.pclk2, hclk, PCLK2_MAX)?;
+        let (ppre2, pclk2) = apb_prescaler(hclk, pclk2_max)?;
+
+        let (voltage_scale, over_drive) = VoltageScale::for_frequency(hclk);
+
+        Ok(ClockPlan {
+            sysclk_source,
+            pll,
+            hpre,
+            ppre1,
+            ppre2,
+            flash_latency: wait_states(hclk, self.voltage_range),
+            voltage_scale,
+            over_drive,
+            spread_spectrum,
+            clocks: Clocks {
+                hse: self.hse,
+                sysclk: Hertz(sysclk),
+                hclk: Hertz(hclk),
+                pclk1: Hertz(pclk1),
+                pclk2: Hertz(pclk2),
+                timclk1: Hertz(timer_clock_freq(hclk, hclk / pclk1, TimerPrescaler::PRE0)),
+                timclk2: Hertz(timer_clock_freq(hclk, hclk / pclk2, TimerPrescaler::PRE0)),
+                pll48clk: pll.map(|pll| Hertz(pll.pll48_freq(input))),
+            },
+        })
+    }
+
+    /// Programs the clock tree and returns the resulting frequencies.
+    ///
+    /// SYSCLK is moved to HSI while the PLL is reconfigured, so this can be
+    /// called again later to change the frequencies. A HSE already running
+    /// in the wanted mode is left alone. PLLI2S and PLLSAI share the source
+    /// and M divider of the main PLL, so changing either while one of them
+    /// runs is refused with [`Error::AuxiliaryPllRunning`].
+    pub fn freeze(self) -> Result<Clocks> {
+        let plan = self.solve()?;
+
+        let rcc = rcc();
+        let flash = flash();
+        let pwr = pwr();
+
+        if let Some(pll) = plan.pll {
+            let input_changes = rcc.pll_clock_source() != pll.source
+                || rcc.pllcfgr.pll_get_division_factor() != pll.m;
+            let auxiliary_running =
+                rcc.cr.pll_i2s_get_state() == State::ON || rcc.cr.pll_sai_get_state() == State::ON;
+
+            if input_changes && auxiliary_running {
+                return Err(Error::AuxiliaryPllRunning);
+            }
+        }
+
+        // run from HSI while everything else changes
+        rcc.cr.hsi_set(State::ON);
+        wait_for(READY_TIMEOUT, || rcc.cr.hsi_is_ready(), Error::Timeout("HSI ready"))?;
+
+        rcc.cfgr.sysclock_set_clock_source(SystemClockSource::HSI);
+        wait_for(
+            READY_TIMEOUT,
+            || rcc.cfgr.sysclock_get_used_clock_source() == SystemClockSource::HSI,
+            Error::Timeout("switch to HSI")
+        )?;
+
+        rcc.cr.pll_set(State::OFF);
+        wait_for(READY_TIMEOUT, || !rcc.cr.pll_is_ready(), Error::Timeout("PLL stop"))?;
+
+        // the regulator scale can only change while the PLL is off
+        pwr.enable_clock();
+        if pwr.over_drive_is_enabled() && !plan.over_drive {
+            pwr.disable_over_drive();
+        }
+        pwr.set_voltage_scale(plan.voltage_scale);
+
+        if let Some(Hertz(freq)) = self.hse {
+            let bypass = if self.hse_bypass { State::ON } else { State::OFF };
+
+            // HSEBYP can only change while HSE is off
+            if !rcc.cr.hse_is_ready() || rcc.cr.hse_bypass_get_state() != bypass {
+                rcc.cr.hse_set(State::OFF);
+                wait_for(READY_TIMEOUT, || !rcc.cr.hse_is_ready(), Error::Timeout("HSE stop"))?;
+
+                rcc.cr.hse_bypass_set(bypass);
+                rcc.cr.hse_set(State::ON);
+                wait_for(READY_TIMEOUT, || rcc.cr.hse_is_ready(), Error::Timeout("HSE ready"))?;
+            }
+
+            set_external_osc_freq(freq);
+        }
+
+        // more wait states are always safe, so raise them before the clock
+        if plan.flash_latency > flash.get_latency() {
+            flash.set_latency(plan.flash_latency);
+        }
+
+        rcc.cfgr.ahb_set_prescaler(plan.hpre);
+        rcc.cfgr.apb1_set_prescaler(plan.ppre1);
+        rcc.cfgr.apb2_set_prescaler(plan.ppre2);
+        rcc.set_timer_prescaler(TimerPrescaler::PRE0);
+
+        if let Some(pll) = plan.pll {
+            rcc.pllcfgr.pll_set_clock_source(pll.source);
+            rcc.pllcfgr.pll_set_division_factor(pll.m);
+            rcc.pllcfgr.pll_set_multiplication_factor(pll.n);
+            rcc.pllcfgr.pll_set_sysclock_difision_factor(pll.p);
+            rcc.pllcfgr.pll_set_usb_sdio_rng_division_factor(pll.q);
+            rcc.set_spread_spectrum(plan.spread_spectrum.as_ref());
+
+            rcc.cr.pll_set(State::ON);
+            wait_for(READY_TIMEOUT, || rcc.cr.pll_is_ready(), Error::Timeout("PLL ready"))?;
+            wait_for(
+                READY_TIMEOUT,
+                || pwr.voltage_scale_is_ready(),
+                Error::Timeout("voltage scale")
+            )?;
+
+            if plan.over_drive {
+                pwr.enable_over_drive().map_err(|_| Error::Timeout("over-drive"))?;
+            }
+        }
+
+        rcc.cfgr.sysclock_set_clock_source(plan.sysclk_source);
+        wait_for(
+            READY_TIMEOUT,
+            || rcc.cfgr.sysclock_get_used_clock_source() == plan.sysclk_source,
+            Error::Timeout("SYSCLK switch")
+        )?;
+
+        flash.set_latency(plan.flash_latency);
+        flash.enable_acceleration();
+
+        if plan.sysclk_source != SystemClockSource::HSI {
+            rcc.cr.hsi_set(State::OFF);
+        }
+
+        Ok(plan.clocks)
+    }
+}
+
+/// Finds the PLL factors giving the SYSCLK closest to, but not above,
+/// `target`. Ties go to the highest PLL input frequency, which has the lowest
+/// jitter.
+fn solve_pll(
+    source: PLLClockSource,
+    input: u32,
+    target: u32,
+    require_pll48: bool
+) -> Result<PllConfig> {
+    const DIVIDERS: [(u32, PLLSysClockDivisionFactor); 4] = [
+        (2, PLLSysClockDivisionFactor::DividedBy2),
+        (4, PLLSysClockDivisionFactor::DividedBy4),
+        (6, PLLSysClockDivisionFactor::DividedBy6),
+        (8, PLLSysClockDivisionFactor::DividedBy8),
+    ];
+
+    let mut best: Option<(u32, PllConfig)> = None;
+
+    for m in PLLM_MIN..=PLLM_MAX {
+        // PLL input has to stay between 1 and 2 MHz
+        if input < PLL_IN_MIN * m || input > PLL_IN_MAX * m {
+            continue;
+        }
+
+        for &(p_value, p) in DIVIDERS.iter() {
+            // highest N not overshooting the target, lower ones are only
+            // worth trying when searching for an exact PLL48CLK
+            let n_max = ((target as u64 * p_value as u64 * m as u64) / input as u64) as u32;
+            let n_max = n_max.min(PLLN_MAX);
+            let n_min = if require_pll48 { PLLN_MIN } else { n_max.max(PLLN_MIN) };
+
+            for n in (n_min..=n_max).rev() {
+                let vco = ((input as u64 * n as u64) / m as u64) as u32;
+                if vco > VCO_MAX {
+                    continue;
+                }
+                if vco < VCO_MIN {
+                    break;
+                }
+
+                // smallest divider keeping PLL48CLK at or below 48 MHz
+                let q = vco.div_ceil(PLL48_FREQ).clamp(PLLQ_MIN, PLLQ_MAX);
+                let pll48_exact =
+                    input as u64 * n as u64 == PLL48_FREQ as u64 * q as u64 * m as u64;
+                if require_pll48 && !pll48_exact {
+                    continue;
+                }
+
+                let pll = PllConfig { source, m, n, p, q };
+                let error = target - pll.sysclk_freq(input);
+                if best.map_or(true, |(best_error, _)| error < best_error) {
+                    best = Some((error, pll));
+                }
+
+                break;
+            }
+        }
+    }
+
+    match best {
+        Some((_, pll)) => Ok(pll),
+        None if require_pll48 => Err(Error::Pll48Unreachable),
+        None => Err(Error::SysclkUnreachable(target)),
+    }
+}
+
+fn bus_limit(requested: Option<Hertz>, hclk: u32, max: u32) -> Result<u32> {
+    match requested {
+        Some(Hertz(freq)) if freq > max => Err(Error::BusClockOutOfRange(freq)),
+        Some(Hertz(freq)) => Ok(freq),
+        None => Ok(hclk.min(max)),
+    }
+}
+
+/// Smallest AHB prescaler bringing `sysclk` down to `max` or below
+fn ahb_prescaler(sysclk: u32, max: u32) -> Result<(AHBPrescaler, u32)> {
+    const PRESCALERS: [(u32, AHBPrescaler); 9] = [
+        (1, AHBPrescaler::NotDivided),
+        (2, AHBPrescaler::DividedBy2),
+        (4, AHBPrescaler::DividedBy4),
+        (8, AHBPrescaler::DividedBy8),
+        (16, AHBPrescaler::DividedBy16),
+        (64, AHBPrescaler::DividedBy64),
+        (128, AHBPrescaler::DividedBy128),
+        (256, AHBPrescaler::DividedBy256),
+        (512, AHBPrescaler::DividedBy512),
+    ];
+
+    PRESCALERS.iter()
+        .find(|(div, _)| sysclk / div <= max)
+        .map(|&(div, pre)| (pre, sysclk / div))
+        .ok_or(Error::BusClockOutOfRange(max))
+}
+
+/// Smallest APB prescaler bringing `hclk` down to `max` or below
+fn apb_prescaler(hclk: u32, max: u32) -> Result<(APBPrescaler, u32)> {
+    const PRESCALERS: [(u32, APBPrescaler); 5] = [
+        (1, APBPrescaler::NotDivided),
+        (2, APBPrescaler::DividedBy2),
+        (4, APBPrescaler::DividedBy4),
+        (8, APBPrescaler::DividedBy8),
+        (16, APBPrescaler::DividedBy16),
+    ];
+
+    PRESCALERS.iter()
+        .find(|(div, _)| hclk / div <= max)
+        .map(|&(div, pre)| (pre, hclk / div))
+        .ok_or(Error::BusClockOutOfRange(max))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ rcc::SpreadSelect, time::U32Ext };
+
+    use super::*;
+
+    fn check(plan: &ClockPlan) {
+        let clocks = &plan.clocks;
+        assert!(clocks.sysclk.raw() <= SYSCLK_MAX);
+        assert!(clocks.hclk.raw() <= clocks.sysclk.raw());
+        assert!(clocks.pclk1.raw() <= PCLK1_MAX);
+        assert!(clocks.pclk2.raw() <= PCLK2_MAX);
+
+        if let Some(pll) = plan.pll {
+            let input = clocks.hse.map_or(HSI_FREQ, |f| f.raw());
+            assert!((PLLM_MIN..=PLLM_MAX).contains(&pll.m));
+            assert!((PLLN_MIN..=PLLN_MAX).contains(&pll.n));
+            assert!((PLLQ_MIN..=PLLQ_MAX).contains(&pll.q));
+            assert!((PLL_IN_MIN..=PLL_IN_MAX).contains(&(input / pll.m)));
+            assert!((VCO_MIN..=VCO_MAX).contains(&pll.vco_freq(input)));
+            assert!(pll.pll48_freq(input) <= PLL48_FREQ);
+        }
+    }
+
+    #[test]
+    fn test_solve_hse_targets() {
+        for hse in [8u32, 12, 16, 25] {
+            for sysclk in [24u32, 48, 72, 84, 100, 120, 144, 168, 180] {
+                let plan = ClockConfig::new()
+                    .hse(hse.MHz())
+                    .sysclk(sysclk.MHz())
+                    .solve()
+                    .unwrap();
+
+                check(&plan);
+                assert_eq!(plan.sysclk_source, SystemClockSource::PLL);
+                assert_eq!(plan.clocks.sysclk(), sysclk.MHz(), "{} MHz HSE", hse);
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_180mhz_from_8mhz_hse() {
+        let plan = ClockConfig::new()
+            .hse(8.MHz())
+            .sysclk(180.MHz())
+            .pclk1(45.MHz())
+            .solve()
+            .unwrap();
+
+        let pll = plan.pll.unwrap();
+        assert_eq!((pll.m, pll.n, pll.p), (4, 180, PLLSysClockDivisionFactor::DividedBy2));
+        assert_eq!(plan.hpre, AHBPrescaler::NotDivided);
+        assert_eq!(plan.ppre1, APBPrescaler::DividedBy4);
+        assert_eq!(plan.ppre2, APBPrescaler::DividedBy2);
+        assert_eq!(plan.flash_latency, 5);
+        assert_eq!(plan.voltage_scale, VoltageScale::Scale1);
+        assert!(plan.over_drive);
+        assert_eq!(plan.clocks.hclk(), 180.MHz());
+        assert_eq!(plan.clocks.pclk1(), 45.MHz());
+        assert_eq!(plan.clocks.timclk1(), 90.MHz());
+        assert_eq!(plan.clocks.timclk2(), 180.MHz());
+        assert_eq!(plan.clocks.pclk2(), 90.MHz());
+    }
+
+    #[test]
+    fn test_solve_pll48() {
+        for hse in [8u32, 12, 16, 25] {
+            let plan = ClockConfig::new()
+                .hse(hse.MHz())
+                .sysclk(168.MHz())
+                .require_pll48clk()
+                .solve()
+                .unwrap();
+
+            check(&plan);
+            assert_eq!(plan.clocks.sysclk(), 168.MHz());
+            assert_eq!(plan.clocks.pll48clk(), Some(48.MHz()));
+        }
+
+        // no VCO frequency is a multiple of both 180 and 48 MHz, so SYSCLK
+        // drops to the closest frequency that keeps USB working
+        let plan = ClockConfig::new()
+            .hse(8.MHz())
+            .sysclk(180.MHz())
+            .require_pll48clk()
+            .solve()
+            .unwrap();
+        assert_eq!(plan.clocks.sysclk(), 168.MHz());
+        assert_eq!(plan.clocks.pll48clk(), Some(48.MHz()));
+    }
+
+    #[test]
+    fn test_solve_without_pll() {
+        let plan = ClockConfig::new().solve().unwrap();
+        assert_eq!(plan.sysclk_source, SystemClockSource::HSI);
+        assert_eq!(plan.pll, None);
+        assert_eq!(plan.clocks.sysclk(), 16.MHz());
+        assert_eq!(plan.clocks.pclk1(), 16.MHz());
+        assert_eq!(plan.flash_latency, 0);
+        assert_eq!(plan.voltage_scale, VoltageScale::Scale3);
+        assert!(!plan.over_drive);
+
+        let plan = ClockConfig::new().hse(25.MHz()).hclk(12.MHz()).solve().unwrap();
+        assert_eq!(plan.sysclk_source, SystemClockSource::HSE);
+        assert_eq!(plan.hpre, AHBPrescaler::DividedBy4);
+        assert_eq!(plan.clocks.hclk().raw(), 6_250_000);
+
+        let plan = ClockConfig::new().sysclk(180.MHz()).solve().unwrap();
+        check(&plan);
+        assert_eq!(plan.pll.unwrap().source, PLLClockSource::HSI);
+        assert_eq!(plan.clocks.sysclk(), 180.MHz());
+    }
+
+    #[test]
+    fn test_solve_spread_spectrum() {
+        let ss = SpreadSpectrum::new(Hertz(10_000), 50, SpreadSelect::CenterSpread);
+
+        // solved for the 2 MHz PLL input and N = 180 of the plan
+        let plan = ClockConfig::new().hse(8.MHz()).sysclk(180.MHz()).spread_spectrum(ss);
+        let settings = plan.solve().unwrap().spread_spectrum.unwrap();
+        assert_eq!((settings.modper, settings.incstep), (50, 118));
+
+        // the modulation needs the PLL even without a SYSCLK target
+        let plan = ClockConfig::new().spread_spectrum(ss).solve().unwrap();
+        check(&plan);
+        assert_eq!(plan.sysclk_source, SystemClockSource::PLL);
+        assert_eq!(plan.clocks.sysclk(), 16.MHz());
+        assert!(plan.spread_spectrum.is_some());
+
+        assert_eq!(ClockConfig::new().solve().unwrap().spread_spectrum, None);
+
+        let ss = SpreadSpectrum::new(Hertz(20_000), 50, SpreadSelect::CenterSpread);
+        assert_eq!(
+            ClockConfig::new().spread_spectrum(ss).solve(),
+            Err(Error::SpreadSpectrumOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        assert_eq!(
+            ClockConfig::new().hse(3.MHz()).solve(),
+            Err(Error::InvalidHseFrequency(3_000_000))
+        );
+        assert!(ClockConfig::new().hse_bypass(3.MHz()).solve().is_ok());
+        assert_eq!(
+            ClockConfig::new().sysclk(200.MHz()).solve(),
+            Err(Error::SysclkOutOfRange(200_000_000))
+        );
+        assert_eq!(
+            ClockConfig::new().sysclk(168.MHz()).pclk1(84.MHz()).solve(),
+            Err(Error::BusClockOutOfRange(84_000_000))
+        );
+    }
+
+    #[test]
+    fn test_solve_never_exceeds_target() {
+        let plan = ClockConfig::new().hse(25.MHz()).sysclk(Hertz(123_456_789)).solve().unwrap();
+        check(&plan);
+        assert!(plan.clocks.sysclk().raw() <= 123_456_789);
+        assert!(plan.clocks.sysclk().raw() >= 123_000_000);
+    }
+
+    #[test]
+    fn test_clocks_read_back() {
+        let mut rcc = RCC::default();
+
+        let clocks = rcc.clocks();
+        assert_eq!(clocks.sysclk(), 16.MHz());
+        assert_eq!(clocks.hse(), None);
+        assert_eq!(clocks.pll48clk(), None);
+
+        // PLLRDY, SWS = PLL, AHB / 2
+        rcc.cr.set(1 << 25);
+        rcc.cfgr.set((0b10 << 2) | (0b1000 << 4));
+
+        // HSI / 16 * 336 / 4 = 84 MHz, / 7 = 48 MHz
+        rcc.pllcfgr.set(16 | (336 << 6) | (0b01 << 16) | (7 << 24));
+
+        let clocks = rcc.clocks();
+        assert_eq!(clocks.sysclk(), 84.MHz());
+        assert_eq!(clocks.hclk(), 42.MHz());
+        assert_eq!(clocks.hse(), None);
+        assert_eq!(clocks.pll48clk(), Some(48.MHz()));
+    }
+}
@@ -0,0 +1,115 @@
+//! Clock security system.
+//!
+//! Once enabled, a HSE failure makes the hardware switch SYSCLK to HSI, stop
+//! the PLL if HSE feeds it and raise an NMI. The NMI handler defined here
+//! acknowledges it, applies the configured [`CssFallback`] and hands the new
+//! frequencies to the application hook so baud rates and timers can follow.
+
+use core::{ ptr::addr_of_mut, sync::atomic::{ compiler_fence, Ordering } };
+
+use crate::State;
+
+use super::{ clocks::Result, rcc, ClockConfig, Clocks, RCC };
+
+/// Clock tree to run from after a HSE failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CssFallback {
+    /// Stay on HSI at 16 MHz as left by the hardware
+    Hsi,
+
+    /// Rebuild the clock tree with the PLL fed by HSI, the HSE part of the
+    /// configuration is ignored
+    Pll(ClockConfig),
+}
+
+/// Called from the NMI with the frequencies the device runs at after the
+/// fallback, or with the error that made rebuilding the PLL fail. The device
+/// then runs from whatever the failed attempt left, [`RCC::clocks`] tells
+/// which.
+pub type CssHook = fn(Result<Clocks>);
+
+struct CssState {
+    fallback: CssFallback,
+    hook: Option<CssHook>,
+}
+
+static mut CSS_STATE: CssState = CssState {
+    fallback: CssFallback::Hsi,
+    hook: None,
+};
+
+impl RCC {
+    /// Enables the clock security system on HSE.
+    ///
+    /// HSE has to be ready already, the detector only runs while it is. CSS
+    /// is turned off while the fallback is replaced, the NMI cannot be masked
+    /// and must not see it half written.
+    pub fn enable_css(&mut self, fallback: CssFallback, hook: Option<CssHook>) {
+        self.cr.css_set(State::OFF);
+        compiler_fence(Ordering::SeqCst);
+
+        unsafe {
+            let state = &mut *addr_of_mut!(CSS_STATE);
+            state.fallback = fallback;
+            state.hook = hook;
+        }
+
+        compiler_fence(Ordering::SeqCst);
+        self.cr.css_set(State::ON);
+    }
+
+    #[inline]
+    pub fn disable_css(&mut self) {
+        self.cr.css_set(State::OFF);
+    }
+
+    #[inline]
+    pub fn css_is_enabled(&self) -> bool {
+        self.cr.css_get_state() == State::ON
+    }
+
+    /// Returns `true` if a HSE failure has been detected and not acknowledged
+    #[inline]
+    pub fn hse_failure_detected(&self) -> bool {
+        self.cir.css_is_hse_failure_inerrupt_set()
+    }
+}
+
+/// Acknowledges a HSE failure and applies the fallback.
+///
+/// Returns `None` if the NMI was not raised by the clock security system,
+/// otherwise the outcome also handed to the [`CssHook`]. Called by the NMI
+/// handler of the `rt` feature, other runtimes have to call it from their own
+/// NMI handler.
+pub fn handle_hse_failure() -> Option<Result<Clocks>> {
+    let rcc = rcc();
+
+    if !rcc.hse_failure_detected() {
+        return None;
+    }
+
+    rcc.cir.css_clear_hse_failure_iterrupt(true);
+    rcc.cr.css_set(State::OFF);
+    rcc.cr.hse_set(State::OFF);
+
+    // the NMI cannot be masked, but the state only changes while CSS is off
+    let state = unsafe { &*addr_of_mut!(CSS_STATE) };
+
+    let clocks = match state.fallback {
+        CssFallback::Hsi => Ok(rcc.clocks()),
+        CssFallback::Pll(config) => config.without_hse().freeze(),
+    };
+
+    if let Some(hook) = state.hook {
+        hook(clocks);
+    }
+
+    Some(clocks)
+}
+
+/// NMI handler, only the clock security system raises it on the STM32F42x
+#[cfg(feature = "rt")]
+#[no_mangle]
+unsafe extern "C" fn __nmi_handler() {
+    handle_hse_failure();
+}
@@ -0,0 +1,333 @@
+//! Low-speed oscillators and RTC clock selection.
+//!
+//! LSE and the RTC clock selection live in the backup domain, which is write
+//! protected until PWR DBP is set and keeps its state across resets. RTCSEL
+//! can only be written once, changing it afterwards takes a backup domain
+//! reset that also clears the RTC and the backup registers, so
+//! [`LowSpeedConfig`] only does it when explicitly allowed.
+
+use crate::{ pwr::pwr, time::Hertz, wait_for, PeripheralClock, State, READY_TIMEOUT };
+
+use super::{
+    clocks::{ Error, Result },
+    external_osc_freq,
+    rcc,
+    RTCClockSource,
+    RCC,
+};
+
+/// Frequency of the watch crystal expected on OSC32_IN/OSC32_OUT
+pub const LSE_FREQ: u32 = 32_768;
+
+/// Nominal LSI frequency, the actual one varies between 17 and 47 kHz
+pub const LSI_FREQ: u32 = 32_000;
+
+/// Highest HSE / RTCPRE accepted by the RTC
+const RTC_HSE_MAX: u32 = 1_000_000;
+const RTCPRE_MIN: u32 = 2;
+const RTCPRE_MAX: u32 = 31;
+
+/// Polling iterations before giving up on the LSE, a crystal can take up to
+/// two seconds to start
+const LSE_STARTUP_TIMEOUT: u32 = 0x0400_0000;
+
+/// Smallest RTCPRE bringing `hse` down to 1 MHz, `None` if HSE is too fast
+pub fn rtc_hse_prescaler(hse: u32) -> Option<u8> {
+    let div = hse.div_ceil(RTC_HSE_MAX).max(RTCPRE_MIN);
+
+    if div <= RTCPRE_MAX {
+        Some(div as u8)
+    } else {
+        None
+    }
+}
+
+/// RTCSEL is write-once, it can only be set while it still selects no clock
+fn rtc_source_locked(selected: RTCClockSource, wanted: RTCClockSource) -> bool {
+    selected != RTCClockSource::None && selected != wanted
+}
+
+/// An RTC meant to run from LSE can be moved to LSI if the fallback is
+/// allowed and RTCSEL can still be set to LSI
+fn can_fall_back_to_lsi(
+    rtc: Option<RTCClockSource>,
+    lsi_fallback: bool,
+    selected: RTCClockSource
+) -> bool {
+    rtc == Some(RTCClockSource::LSE)
+        && lsi_fallback
+        && !rtc_source_locked(selected, RTCClockSource::LSI)
+}
+
+/// Low-speed oscillators and RTC clock builder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LowSpeedConfig {
+    lse: bool,
+    lse_bypass: bool,
+    lsi: bool,
+    rtc: Option<RTCClockSource>,
+    lsi_fallback: bool,
+    allow_backup_domain_reset: bool,
+}
+
+/// Frozen low-speed clocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LowSpeedClocks {
+    lse: Option<Hertz>,
+    lsi: Option<Hertz>,
+    rtc_source: RTCClockSource,
+    rtc: Option<Hertz>,
+}
+
+impl LowSpeedClocks {
+    #[inline]
+    pub fn lse(&self) -> Option<Hertz> {
+        self.lse
+    }
+
+    #[inline]
+    pub fn lsi(&self) -> Option<Hertz> {
+        self.lsi
+    }
+
+    /// Source the RTC actually runs from, LSI if the LSE fallback kicked in
+    #[inline]
+    pub fn rtc_source(&self) -> RTCClockSource {
+        self.rtc_source
+    }
+
+    /// RTC clock, `None` if the RTC is not clocked
+    #[inline]
+    pub fn rtc(&self) -> Option<Hertz> {
+        self.rtc
+    }
+}
+
+impl Default for LowSpeedConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LowSpeedConfig {
+    /// Leaves both oscillators and the RTC clock untouched
+    pub const fn new() -> Self {
+        Self {
+            lse: false,
+            lse_bypass: false,
+            lsi: false,
+            rtc: None,
+            lsi_fallback: false,
+            allow_backup_domain_reset: false,
+        }
+    }
+
+    /// Starts the 32.768 kHz crystal
+    pub fn lse(mut self) -> Self {
+        self.lse = true;
+        self.lse_bypass = false;
+        self
+    }
+
+    /// Uses an external 32.768 kHz clock on OSC32_IN instead of a crystal
+    pub fn lse_bypass(mut self) -> Self {
+        self.lse = true;
+        self.lse_bypass = true;
+        self
+    }
+
+    /// Starts the internal RC oscillator, also needed by the IWDG
+    pub fn lsi(mut self) -> Self {
+        self.lsi = true;
+        self
+    }
+
+    /// Clocks the RTC from `source`, starting the oscillator it needs. HSE
+    /// has to be running already.
+    pub fn rtc(mut self, source: RTCClockSource) -> Self {
+        self.rtc = Some(source);
+        self
+    }
+
+    /// Runs the RTC from LSI when it should use a LSE that does not start
+    pub fn lsi_fallback(mut self) -> Self {
+        self.lsi_fallback = true;
+        self
+    }
+
+    /// Lets [`freeze`](Self::freeze) reset the backup domain when RTCSEL
+    /// already holds another source, which clears the RTC and backup
+    /// registers
+    pub fn allow_backup_domain_reset(mut self) -> Self {
+        self.allow_backup_domain_reset = true;
+        self
+    }
+
+    /// Starts the oscillators and selects the RTC clock
+    pub fn freeze(self) -> Result<LowSpeedClocks> {
+        let rcc = rcc();
+        let pwr = pwr();
+
+        pwr.enable_clock();
+        pwr.enable_backup_access();
+        wait_for(
+            READY_TIMEOUT,
+            || pwr.backup_access_is_enabled(),
+            Error::Timeout("backup access")
+        )?;
+
+        let mut selected = rcc.bdcr.rtc_get_clock_source();
+
+        if let Some(source) = self.rtc {
+            if rtc_source_locked(selected, source) {
+                if !self.allow_backup_domain_reset {
+                    return Err(Error::RtcSourceLocked(selected));
+                }
+
+                rcc.bdcr.backup_domain_reset(true);
+                rcc.bdcr.backup_domain_reset(false);
+                selected = RTCClockSource::None;
+            }
+        }
+
+        let mut rtc = self.rtc;
+        let rtc_on_lse = rtc == Some(RTCClockSource::LSE);
+        let can_fall_back = can_fall_back_to_lsi(rtc, self.lsi_fallback, selected);
+
+        let mut lse = None;
+        if self.lse || rtc_on_lse {
+            match rcc.start_lse(self.lse_bypass) {
+                Ok(()) => lse = Some(Hertz(LSE_FREQ)),
+                Err(_) if can_fall_back => {
+                    rcc.bdcr.lse_set(State::OFF);
+                    rtc = Some(RTCClockSource::LSI);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut lsi = None;
+        if self.lsi || rtc == Some(RTCClockSource::LSI) {
+            rcc.csr.lsi_set(State::ON);
+            wait_for(READY_TIMEOUT, || rcc.csr.lsi_is_ready(), Error::Timeout("LSI ready"))?;
+            lsi = Some(Hertz(LSI_FREQ));
+        }
+
+        if rtc == Some(RTCClockSource::HSE) {
+            if !rcc.cr.hse_is_ready() {
+                return Err(Error::HseNotRunning);
+            }
+
+            let hse = external_osc_freq();
+            let rtcpre = rtc_hse_prescaler(hse).ok_or(Error::InvalidHseFrequency(hse))?;
+            rcc.cfgr.rtc_set_division_factor(rtcpre);
+        }
+
+        if let Some(source) = rtc {
+            if selected != source {
+                rcc.bdcr.rtc_set_clock_source(source);
+            }
+            rcc.bdcr.rtc_enable(true);
+        }
+
+        Ok(LowSpeedClocks {
+            lse,
+            lsi,
+            rtc_source: rcc.bdcr.rtc_get_clock_source(),
+            rtc: match rcc.rtc_clk_freq() {
+                0 => None,
+                freq => Some(Hertz(freq)),
+            },
+        })
+    }
+}
+
+impl RCC {
+    /// Starts LSE, left alone if it already runs in the wanted mode since it
+    /// survives resets with the rest of the backup domain
+    fn start_lse(&mut self, bypass: bool) -> Result<()> {
+        let wanted = if bypass { State::ON } else { State::OFF };
+
+        if self.bdcr.lse_is_ready() && self.bdcr.lse_bypass_get_state() == wanted {
+            return Ok(());
+        }
+
+        self.bdcr.lse_set(State::OFF);
+        wait_for(READY_TIMEOUT, || !self.bdcr.lse_is_ready(), Error::Timeout("LSE stop"))?;
+
+        self.bdcr.lse_bypass_set(wanted);
+        self.bdcr.lse_set(State::ON);
+
+        wait_for(LSE_STARTUP_TIMEOUT, || self.bdcr.lse_is_ready(), Error::Timeout("LSE ready"))
+    }
+
+    /// RTC clock frequency, 0 if no source is selected
+    pub fn rtc_clk_freq(&self) -> u32 {
+        match self.bdcr.rtc_get_clock_source() {
+            RTCClockSource::None => 0,
+            RTCClockSource::LSE => LSE_FREQ,
+            RTCClockSource::LSI => LSI_FREQ,
+            RTCClockSource::HSE => match self.cfgr.rtc_get_division_factor() as u32 {
+                div if div >= RTCPRE_MIN => external_osc_freq() / div,
+                _ => 0,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rtc_hse_prescaler() {
+        assert_eq!(rtc_hse_prescaler(1_000_000), Some(2));
+        assert_eq!(rtc_hse_prescaler(8_000_000), Some(8));
+        assert_eq!(rtc_hse_prescaler(12_000_000), Some(12));
+        assert_eq!(rtc_hse_prescaler(25_000_000), Some(25));
+        assert_eq!(rtc_hse_prescaler(26_000_000), Some(26));
+        assert_eq!(rtc_hse_prescaler(8_500_000), Some(9));
+        assert_eq!(rtc_hse_prescaler(50_000_000), None);
+    }
+
+    #[test]
+    fn test_rtc_source_locked() {
+        // never written since the last backup domain reset
+        assert!(!rtc_source_locked(RTCClockSource::None, RTCClockSource::LSE));
+        assert!(!rtc_source_locked(RTCClockSource::None, RTCClockSource::HSE));
+
+        assert!(!rtc_source_locked(RTCClockSource::LSE, RTCClockSource::LSE));
+        assert!(rtc_source_locked(RTCClockSource::LSE, RTCClockSource::LSI));
+        assert!(rtc_source_locked(RTCClockSource::HSE, RTCClockSource::LSE));
+    }
+
+    #[test]
+    fn test_can_fall_back_to_lsi() {
+        assert!(can_fall_back_to_lsi(Some(RTCClockSource::LSE), true, RTCClockSource::None));
+        assert!(can_fall_back_to_lsi(Some(RTCClockSource::LSE), true, RTCClockSource::LSI));
+
+        // RTCSEL already holds LSE, moving to LSI takes a backup domain reset
+        assert!(!can_fall_back_to_lsi(Some(RTCClockSource::LSE), true, RTCClockSource::LSE));
+
+        assert!(!can_fall_back_to_lsi(Some(RTCClockSource::LSE), false, RTCClockSource::None));
+        assert!(!can_fall_back_to_lsi(Some(RTCClockSource::HSE), true, RTCClockSource::None));
+        assert!(!can_fall_back_to_lsi(None, true, RTCClockSource::None));
+    }
+
+    #[test]
+    fn test_rtc_clk_freq() {
+        let mut rcc = RCC::default();
+        assert_eq!(rcc.rtc_clk_freq(), 0);
+
+        rcc.bdcr.rtc_set_clock_source(RTCClockSource::LSE);
+        assert_eq!(rcc.rtc_clk_freq(), LSE_FREQ);
+
+        rcc.bdcr.rtc_set_clock_source(RTCClockSource::LSI);
+        assert_eq!(rcc.rtc_clk_freq(), LSI_FREQ);
+
+        // RTCPRE values 0 and 1 mean no clock
+        rcc.bdcr.rtc_set_clock_source(RTCClockSource::HSE);
+        rcc.cfgr.rtc_set_division_factor(1);
+        assert_eq!(rcc.rtc_clk_freq(), 0);
+    }
+}
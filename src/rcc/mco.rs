@@ -0,0 +1,41 @@
+//! Microcontroller clock outputs, MCO1 on PA8 and MCO2 on PC9.
+
+use crate::{
+    gpio::{ self, pin::Pin, port::Port, OutputType, PinConfig, Pull, Speed },
+    PeripheralClock,
+};
+
+use super::{ MCO2ClockSource, MCOClockSource, MCOPrescaler, RCC };
+
+/// Alternate function of PA8 and PC9 carrying MCO1 and MCO2
+const MCO_AF: u8 = 0;
+
+/// Enables the port clock and hands `pin` over to the MCO
+fn init_mco_pin(port: Port, pin: Pin) {
+    let gpio = gpio::port(port);
+    gpio.enable_clock();
+    gpio.init_pins(
+        pin,
+        PinConfig::Alternate(MCO_AF, OutputType::PushPull, Speed::VeryHigh, Pull::None)
+    );
+}
+
+impl RCC {
+    /// Outputs `source / prescaler` on PA8, which must stay at or below
+    /// 100 MHz
+    pub fn configure_mco1(&mut self, source: MCOClockSource, prescaler: MCOPrescaler) {
+        self.cfgr.mco1_set_clock_cource(source);
+        self.cfgr.mco1_set_prescaler(prescaler);
+
+        init_mco_pin(Port::A, Pin::PIN8);
+    }
+
+    /// Outputs `source / prescaler` on PC9, which must stay at or below
+    /// 100 MHz
+    pub fn configure_mco2(&mut self, source: MCO2ClockSource, prescaler: MCOPrescaler) {
+        self.cfgr.mco2_set_clock_cource(source);
+        self.cfgr.mco2_set_prescaler(prescaler);
+
+        init_mco_pin(Port::C, Pin::PIN9);
+    }
+}
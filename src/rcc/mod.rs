@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use core::cell::Cell;
+use core::sync::atomic::{ AtomicU32, Ordering };
 
 use crate::peripheral;
 
@@ -10,6 +10,8 @@ pub use register::{
     AHBPrescaler,
     APBPrescaler,
     I2SClockSource,
+    LCDClockDivisionFactor,
+    MCO2ClockSource,
     MCOClockSource,
     MCOPrescaler,
     PLLClockSource,
@@ -22,7 +24,30 @@ pub use register::{
 };
 
 mod register;
+mod auxiliary;
+pub mod clocks;
+mod css;
+mod lowspeed;
+mod mco;
+mod reset;
+mod sscg;
+mod timer;
+
+pub use self::auxiliary::{ I2sConfig, I2sPlan, PllSaiConfig, PllSaiPlan };
+pub use self::clocks::{ ClockConfig, Clocks };
+pub use self::css::{ handle_hse_failure, CssFallback, CssHook };
+pub use self::lowspeed::{
+    rtc_hse_prescaler,
+    LowSpeedClocks,
+    LowSpeedConfig,
+    LSE_FREQ,
+    LSI_FREQ,
+};
+pub use self::reset::{ ResetCause, ResetFlags };
+pub use self::sscg::{ SpreadSpectrum, SpreadSpectrumSettings };
+pub use self::timer::{ timer_clock_freq, Bus, Timer };
 
+#[derive(Debug, Default)]
 pub struct RCC {
     /// Clock Control Register
     pub cr: ClockControlRegister,
@@ -122,10 +147,27 @@ pub fn rcc() -> &'static mut RCC {
     peripheral(0x4002_3800)
 }
 
-const EXTERNAL_OSC_FREQ: core::cell::Cell<u32> = Cell::new(8_000_000u32);
+/// Frequency of the HSE crystal or external clock, has to be set before any
+/// frequency derived from HSE is read
+static EXTERNAL_OSC_FREQ: AtomicU32 = AtomicU32::new(8_000_000u32);
 
 pub fn set_external_osc_freq(freq: u32) {
-    EXTERNAL_OSC_FREQ.set(freq);
+    EXTERNAL_OSC_FREQ.store(freq, Ordering::Relaxed);
+}
+
+#[inline]
+pub fn external_osc_freq() -> u32 {
+    EXTERNAL_OSC_FREQ.load(Ordering::Relaxed)
+}
+
+fn apb_divider(prescaler: APBPrescaler) -> u32 {
+    match prescaler {
+        APBPrescaler::NotDivided => 1,
+        APBPrescaler::DividedBy2 => 2,
+        APBPrescaler::DividedBy4 => 4,
+        APBPrescaler::DividedBy8 => 8,
+        APBPrescaler::DividedBy16 => 16,
+    }
 }
 
 impl RCC {
@@ -143,11 +185,11 @@ impl RCC {
     pub fn sysclk_freq(&self) -> u32 {
         match self.sysclock_clock_source() {
             SystemClockSource::HSI => 16_000_000u32,
-            SystemClockSource::HSE => EXTERNAL_OSC_FREQ.get(),
+            SystemClockSource::HSE => external_osc_freq(),
             SystemClockSource::PLL => {
                 let freq = match self.pll_clock_source() {
                     PLLClockSource::HSI => 16_000_000u32,
-                    PLLClockSource::HSE => EXTERNAL_OSC_FREQ.get(),
+                    PLLClockSource::HSE => external_osc_freq(),
                 };
 
                 let pllp = match self.pllcfgr.pll_get_sysclock_division_factor() {
@@ -183,27 +225,55 @@ impl RCC {
 
     #[inline]
     pub fn pclk1_freq(&self) -> u32 {
-        let apb1_prescaler = match self.cfgr.apb1_get_prescaler() {
-            APBPrescaler::NotDivided => 1,
-            APBPrescaler::DividedBy2 => 2,
-            APBPrescaler::DividedBy4 => 4,
-            APBPrescaler::DividedBy8 => 8,
-            APBPrescaler::DividedBy16 => 16,
-        };
-
-        self.hclk_freq() / apb1_prescaler
+        self.hclk_freq() / apb_divider(self.cfgr.apb1_get_prescaler())
     }
 
     #[inline]
     pub fn pclk2_freq(&self) -> u32 {
-        let apb2_prescaler = match self.cfgr.apb2_get_prescaler() {
-            APBPrescaler::NotDivided => 1,
-            APBPrescaler::DividedBy2 => 2,
-            APBPrescaler::DividedBy4 => 4,
-            APBPrescaler::DividedBy8 => 8,
-            APBPrescaler::DividedBy16 => 16,
-        };
+        self.hclk_freq() / apb_divider(self.cfgr.apb2_get_prescaler())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frequencies_follow_hse() {
+        let mut rcc = RCC::default();
+
+        assert_eq!(rcc.sysclk_freq(), 16_000_000);
+
+        // SWS = HSE
+        rcc.cfgr.set(0b01 << 2);
+
+        set_external_osc_freq(25_000_000);
+        assert_eq!(rcc.sysclk_freq(), 25_000_000);
+        assert_eq!(rcc.pclk1_freq(), 25_000_000);
+
+        set_external_osc_freq(12_000_000);
+        assert_eq!(rcc.sysclk_freq(), 12_000_000);
+
+        // SWS = PLL, APB1 / 4, APB2 / 2
+        rcc.cfgr.set((0b10 << 2) | (0b101 << 10) | (0b100 << 13));
+
+        // HSE / 12 * 336 / 4 = 84 MHz
+        rcc.pllcfgr.set(12 | (336 << 6) | (0b01 << 16) | (1 << 22) | (7 << 24));
+        assert_eq!(rcc.sysclk_freq(), 84_000_000);
+        assert_eq!(rcc.hclk_freq(), 84_000_000);
+        assert_eq!(rcc.pclk1_freq(), 21_000_000);
+        assert_eq!(rcc.pclk2_freq(), 42_000_000);
+
+        // HSE / 25 * 336 / 4 = 84 MHz
+        set_external_osc_freq(25_000_000);
+        rcc.pllcfgr.set(25 | (336 << 6) | (0b01 << 16) | (1 << 22) | (7 << 24));
+        assert_eq!(rcc.sysclk_freq(), 84_000_000);
+        assert_eq!(rcc.pclk1_freq(), 21_000_000);
+
+        // PLL from HSI is not affected
+        rcc.pllcfgr.set(16 | (336 << 6) | (0b01 << 16) | (7 << 24));
+        assert_eq!(rcc.sysclk_freq(), 84_000_000);
 
-        self.hclk_freq() / apb2_prescaler
+        set_external_osc_freq(8_000_000);
     }
 }
@@ -3,6 +3,7 @@ use register::{ field::derive::RegisterField, register };
 use crate::{ State, gpio::port::PortMask };
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct ClockControlRegister {
     #[bits(1, rw, get = hsi_get_state, set = hsi_set)]
     pub HSION: State,
@@ -63,6 +64,7 @@ pub struct ClockControlRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct PLLConfigurationRegister {
     #[bits(6, rw, get = pll_get_division_factor, set = pll_set_division_factor)]
     pub PLLM: u32,
@@ -98,6 +100,7 @@ pub struct PLLConfigurationRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct ClockConfigurationRegister {
     #[bits(2, rw, get = sysclock_get_clock_source, set = sysclock_set_clock_source)]
     pub SW: SystemClockSource,
@@ -133,10 +136,11 @@ pub struct ClockConfigurationRegister {
     pub MCO2PRE: MCOPrescaler,
 
     #[bits(2, rw, get = mco2_get_clock_source, set = mco2_set_clock_cource)]
-    pub MCO2: MCOClockSource,
+    pub MCO2: MCO2ClockSource,
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct ClockInterruptRegister {
     #[bits(1, r, get = lsi_is_ready_inerrupt_set)]
     pub LSIRDYF: bool,
@@ -215,6 +219,7 @@ pub struct ClockInterruptRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct AHB1PeripheralResetRegister {
     #[bits(11, rwc, get = gpio_get_reset_state, set = gpio_reset, clear = gpio_reset_clear)]
     pub GPIORST: PortMask,
@@ -254,6 +259,7 @@ pub struct AHB1PeripheralResetRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct AHB2PeripheralResetRegister {
     #[bits(1, rw, get = dcmi_is_in_reset_state, set = dcmi_reset)]
     pub DCMIRST: bool,
@@ -278,6 +284,7 @@ pub struct AHB2PeripheralResetRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct AHB3PeripheralResetRegister {
     #[bits(1, rw, get = fmc_is_in_reset_state, set = fmc_reset)]
     pub FMCRST: bool,
@@ -287,6 +294,7 @@ pub struct AHB3PeripheralResetRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct APB1PeripheralResetRegister {
     #[bits(1, rw, get = timer2_is_in_reset_state, set = timer2_reset)]
     pub TIM2RST: bool,
@@ -380,6 +388,7 @@ pub struct APB1PeripheralResetRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct APB2PeripheralResetRegister {
     #[bits(1, rw, get = timer1_is_in_reset_state, set = timer1_reset)]
     pub TIM1RST: bool,
@@ -452,6 +461,7 @@ pub struct APB2PeripheralResetRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct AHB1PeripheralClockRegister {
     #[bits(11, rw, get = gpio_get_enabled, set = gpio_enable)]
     pub GPIOEN: PortMask,
@@ -551,6 +561,7 @@ pub struct AHB1PeripheralClockRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct AHB2PeripheralClockRegister {
     #[bits(1, rwc, get = dcmi_is_enabled, set = dcmi_enable, clear = dcmi_disable)]
     pub DCMIEN: bool,
@@ -581,6 +592,7 @@ pub struct AHB2PeripheralClockRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct AHB3PeripheralClockRegister {
     #[bits(1, rwc, get = fmc_is_enabled, set = fmc_enable, clear = fmc_disable)]
     pub FMCEN: bool,
@@ -590,6 +602,7 @@ pub struct AHB3PeripheralClockRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct APB1PeripheralClockRegister {
     #[bits(1, rwc, get = timer2_is_enabled, set = timer2_enable, clear = timer2_disable)]
     pub TIM2EN: bool,
@@ -683,6 +696,7 @@ pub struct APB1PeripheralClockRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct APB2PeripheralClockRegister {
     #[bits(1, rwc, get = timer1_is_enabled, set = timer1_enable, clear = timer1_disable)]
     pub TIM1EN: bool,
@@ -758,6 +772,7 @@ pub struct APB2PeripheralClockRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct AHB1PeripheralClockLowPowerModeRegister {
     #[bits(11, rw, get = gpio_get_enabled, set = gpio_enable)]
     pub GPIOLPEN: PortMask,
@@ -860,6 +875,7 @@ pub struct AHB1PeripheralClockLowPowerModeRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct BackupDomainControlRegister {
     #[bits(1, rw, get = lse_get_state, set = lse_set)]
     pub LSEON: State,
@@ -890,6 +906,7 @@ pub struct BackupDomainControlRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct ClockControlAndStatusRegister {
     #[bits(1, rw, get = lsi_get_state, set = lsi_set)]
     pub LSION: State,
@@ -926,6 +943,7 @@ pub struct ClockControlAndStatusRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct SpreadSpectrumClockGenerationRegister {
     #[bits(13, rw, get = get_modulation_period, set = set_modulation_period)]
     pub MODPER: u16,
@@ -949,6 +967,7 @@ pub struct SpreadSpectrumClockGenerationRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct PLLI2SConfigurationRegister {
     #[bits(6)]
     __: u32,
@@ -975,6 +994,7 @@ pub struct PLLI2SConfigurationRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct PLLSAIConfigurationRegister {
     #[bits(6)]
     __: u32,
@@ -1001,6 +1021,7 @@ pub struct PLLSAIConfigurationRegister {
 }
 
 #[register(u32)]
+#[derive(Debug, Default)]
 pub struct DedicatedClockConfigurationRegister {
     #[bits(5, rw, get = pll_i2s_sai_get_division_factor, set = pll_i2s_sai_set_division_factor)]
     pub PLLI2SDIVQ: u8,
@@ -1008,12 +1029,18 @@ pub struct DedicatedClockConfigurationRegister {
     #[bits(3)]
     __: u32,
 
-    #[bits(9, rw, get = pll_sai_sai1_get_division_factor, set = pll_sai_sai1_set_division_factor)]
-    pub PLLSAIDIVQ: u16,
+    #[bits(5, rw, get = pll_sai_sai1_get_division_factor, set = pll_sai_sai1_set_division_factor)]
+    pub PLLSAIDIVQ: u8,
 
     #[bits(3)]
     __: u32,
 
+    #[bits(2, rw, get = pll_sai_lcd_get_division_factor, set = pll_sai_lcd_set_division_factor)]
+    pub PLLSAIDIVR: LCDClockDivisionFactor,
+
+    #[bits(2)]
+    __: u32,
+
     #[bits(2, rw, get = sai1a_get_clock_source, set = sai1a_set_clock_source)]
     pub SAI1ASRC: SAIClockSource,
 
@@ -1048,6 +1075,14 @@ pub enum MCOClockSource {
     PLL = 0b11,
 }
 
+#[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MCO2ClockSource {
+    SYSCLK = 0b00,
+    PLLI2S = 0b01,
+    HSE = 0b10,
+    PLL = 0b11,
+}
+
 #[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum I2SClockSource {
     PLLI2S = 0b0,
@@ -1115,6 +1150,14 @@ pub enum SAIClockSource {
     Alternate = 0b10,
 }
 
+#[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LCDClockDivisionFactor {
+    DividedBy2 = 0b00,
+    DividedBy4 = 0b01,
+    DividedBy8 = 0b10,
+    DividedBy16 = 0b11,
+}
+
 #[derive(RegisterField, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TimerPrescaler {
     PRE0 = 0b0,
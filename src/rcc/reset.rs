@@ -0,0 +1,160 @@
+use core::fmt;
+
+use super::RCC;
+
+/// Position of BORRSTF, the lowest reset flag in RCC_CSR
+const CSR_RESET_FLAGS_POS: u32 = 25;
+
+/// Reset flags captured from RCC_CSR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ResetFlags(u8);
+
+impl ResetFlags {
+    pub const BROWNOUT: Self = Self(1 << 0);
+    pub const PIN: Self = Self(1 << 1);
+    pub const POWER_ON: Self = Self(1 << 2);
+    pub const SOFTWARE: Self = Self(1 << 3);
+    pub const INDEPENDENT_WATCHDOG: Self = Self(1 << 4);
+    pub const WINDOW_WATCHDOG: Self = Self(1 << 5);
+    pub const LOW_POWER: Self = Self(1 << 6);
+
+    /// Extracts the flags from a raw RCC_CSR value
+    pub fn from_csr(csr: u32) -> Self {
+        Self((csr >> CSR_RESET_FLAGS_POS) as u8 & 0x7f)
+    }
+
+    #[inline]
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    pub fn contains(&self, flags: Self) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+
+    /// Returns the reset that actually happened.
+    ///
+    /// A power-on reset also sets the BOR and pin flags, a brown-out the pin
+    /// flag, and every internal reset is driven out on NRST so it sets the pin
+    /// flag too. The pin is only reported when nothing else explains it.
+    pub fn cause(&self) -> ResetCause {
+        if self.contains(Self::LOW_POWER) {
+            ResetCause::LowPower
+        } else if self.contains(Self::WINDOW_WATCHDOG) {
+            ResetCause::WindowWatchdog
+        } else if self.contains(Self::INDEPENDENT_WATCHDOG) {
+            ResetCause::IndependentWatchdog
+        } else if self.contains(Self::SOFTWARE) {
+            ResetCause::Software
+        } else if self.contains(Self::POWER_ON) {
+            ResetCause::PowerOn
+        } else if self.contains(Self::BROWNOUT) {
+            ResetCause::Brownout
+        } else if self.contains(Self::PIN) {
+            ResetCause::Pin
+        } else {
+            ResetCause::Unknown
+        }
+    }
+}
+
+impl core::ops::BitOr for ResetFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Primary cause of the last reset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetCause {
+    /// Power-on or power-down reset
+    PowerOn,
+
+    /// Supply dropped below the BOR threshold
+    Brownout,
+
+    /// NRST pin pulled low externally
+    Pin,
+
+    /// SYSRESETREQ from the core
+    Software,
+
+    IndependentWatchdog,
+
+    WindowWatchdog,
+
+    /// Illegal Stop or Standby entry, see the nRST_STOP/nRST_STDBY options
+    LowPower,
+
+    /// No flag was set, they were already cleared since the last reset
+    Unknown,
+}
+
+impl fmt::Display for ResetCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResetCause::PowerOn => f.write_str("Power-on reset"),
+            ResetCause::Brownout => f.write_str("Brown-out reset"),
+            ResetCause::Pin => f.write_str("Pin reset"),
+            ResetCause::Software => f.write_str("Software reset"),
+            ResetCause::IndependentWatchdog => f.write_str("Independent watchdog reset"),
+            ResetCause::WindowWatchdog => f.write_str("Window watchdog reset"),
+            ResetCause::LowPower => f.write_str("Low-power reset"),
+            ResetCause::Unknown => f.write_str("Unknown reset"),
+        }
+    }
+}
+
+impl RCC {
+    /// Reads the reset flags without clearing them
+    #[inline]
+    pub fn reset_flags(&self) -> ResetFlags {
+        ResetFlags::from_csr(self.csr.get())
+    }
+
+    /// Captures all reset flags in a single read, then clears them so the
+    /// next reset is reported on its own
+    pub fn take_reset_flags(&mut self) -> ResetFlags {
+        let flags = self.reset_flags();
+        self.csr.remove_reset_flag(true);
+        flags
+    }
+
+    /// Returns the primary cause of the last reset and clears the flags
+    #[inline]
+    pub fn take_reset_cause(&mut self) -> ResetCause {
+        self.take_reset_flags().cause()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_flags_decoding() {
+        // LSION and LSIRDY are not reset flags
+        assert!(ResetFlags::from_csr(0b11).is_empty());
+
+        // POR also sets PINRSTF and BORRSTF
+        let flags = ResetFlags::from_csr(0x0e00_0000);
+        assert!(flags.contains(ResetFlags::POWER_ON | ResetFlags::BROWNOUT | ResetFlags::PIN));
+        assert_eq!(flags.cause(), ResetCause::PowerOn);
+
+        assert_eq!(ResetFlags::from_csr(0x0600_0000).cause(), ResetCause::Brownout);
+        assert_eq!(ResetFlags::from_csr(0x0400_0000).cause(), ResetCause::Pin);
+        assert_eq!(ResetFlags::from_csr(0x1400_0000).cause(), ResetCause::Software);
+        assert_eq!(ResetFlags::from_csr(0x2400_0000).cause(), ResetCause::IndependentWatchdog);
+        assert_eq!(ResetFlags::from_csr(0x4400_0000).cause(), ResetCause::WindowWatchdog);
+        assert_eq!(ResetFlags::from_csr(0x8400_0000).cause(), ResetCause::LowPower);
+        assert_eq!(ResetFlags::from_csr(0).cause(), ResetCause::Unknown);
+    }
+}
@@ -0,0 +1,166 @@
+//! Spread-spectrum modulation of the main PLL.
+//!
+//! The reference manual derives the register values from the PLL input
+//! frequency, the modulation frequency and the peak deviation:
+//!
+//! - MODPER = round(fPLL_IN / (4 * fMod))
+//! - INCSTEP = round((2^15 - 1) * md * PLLN / (100 * 5 * MODPER))
+//!
+//! with md in percent, and MODPER * INCSTEP must fit in 15 bits.
+
+use crate::{ time::Hertz, State };
+
+use super::{ clocks::{ Error, Result }, SpreadSelect, RCC };
+
+const MODPER_MAX: u32 = (1 << 13) - 1;
+const INCSTEP_MAX: u32 = (1 << 15) - 1;
+
+/// Highest modulation frequency supported by the SSCG
+const MODULATION_FREQ_MAX: u32 = 10_000;
+
+/// Highest peak deviation, in hundredths of a percent
+const DEVIATION_MAX: u32 = 200;
+
+/// Spread-spectrum modulation builder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpreadSpectrum {
+    modulation_freq: Hertz,
+    deviation: u32,
+    spread: SpreadSelect,
+}
+
+/// Values of the SSCGR fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpreadSpectrumSettings {
+    pub modper: u16,
+    pub incstep: u16,
+    pub spread: SpreadSelect,
+}
+
+impl SpreadSpectrum {
+    /// Modulates at `modulation_freq` with a peak `deviation` given in
+    /// hundredths of a percent, 200 being the 2 % maximum
+    pub const fn new(modulation_freq: Hertz, deviation: u32, spread: SpreadSelect) -> Self {
+        Self { modulation_freq, deviation, spread }
+    }
+
+    /// Computes MODPER and INCSTEP for a PLL input of `pll_input` multiplied
+    /// by `plln`
+    pub fn solve(&self, pll_input: u32, plln: u32) -> Result<SpreadSpectrumSettings> {
+        let fmod = self.modulation_freq.raw();
+        if fmod == 0 || fmod > MODULATION_FREQ_MAX {
+            return Err(Error::SpreadSpectrumOutOfRange);
+        }
+        if self.deviation == 0 || self.deviation > DEVIATION_MAX {
+            return Err(Error::SpreadSpectrumOutOfRange);
+        }
+
+        let modper = (pll_input + 2 * fmod) / (4 * fmod);
+        if modper == 0 || modper > MODPER_MAX {
+            return Err(Error::SpreadSpectrumOutOfRange);
+        }
+
+        // md is in hundredths of a percent, hence 100 * 100 * 5
+        let numerator = INCSTEP_MAX as u64 * self.deviation as u64 * plln as u64;
+        let denominator = 50_000 * modper as u64;
+        let incstep = ((numerator + denominator / 2) / denominator) as u32;
+        if incstep == 0 || incstep > INCSTEP_MAX || modper * incstep > INCSTEP_MAX {
+            return Err(Error::SpreadSpectrumOutOfRange);
+        }
+
+        Ok(SpreadSpectrumSettings {
+            modper: modper as u16,
+            incstep: incstep as u16,
+            spread: self.spread,
+        })
+    }
+}
+
+impl RCC {
+    /// Programs and enables the modulation for the PLL factors currently
+    /// set, the PLL has to be off.
+    ///
+    /// [`ClockConfig::freeze`](super::ClockConfig::freeze) rewrites the PLL
+    /// factors and SSCGR, use
+    /// [`ClockConfig::spread_spectrum`](super::ClockConfig::spread_spectrum)
+    /// to modulate a PLL it starts.
+    pub fn configure_spread_spectrum(
+        &mut self,
+        spread_spectrum: &SpreadSpectrum
+    ) -> Result<SpreadSpectrumSettings> {
+        if self.cr.pll_get_state() == State::ON {
+            return Err(Error::PllRunning);
+        }
+
+        let plln = self.pllcfgr.pll_get_mutiplication_factor();
+        let settings = spread_spectrum.solve(self.pll_input_freq(), plln)?;
+        self.set_spread_spectrum(Some(&settings));
+
+        Ok(settings)
+    }
+
+    /// Stops the modulation, the PLL has to be off
+    pub fn disable_spread_spectrum(&mut self) -> Result<()> {
+        if self.cr.pll_get_state() == State::ON {
+            return Err(Error::PllRunning);
+        }
+
+        self.set_spread_spectrum(None);
+
+        Ok(())
+    }
+
+    /// Writes SSCGR, `None` stops the modulation
+    pub(super) fn set_spread_spectrum(&mut self, settings: Option<&SpreadSpectrumSettings>) {
+        match settings {
+            Some(settings) => {
+                self.sscgr.set_modulation_period(settings.modper);
+                self.sscgr.set_increment_step(settings.incstep);
+                self.sscgr.set_spread(settings.spread);
+                self.sscgr.enable_spread_spectrum_modulation(true);
+            }
+            None => self.sscgr.enable_spread_spectrum_modulation(false),
+        }
+    }
+
+    #[inline]
+    pub fn spread_spectrum_is_enabled(&self) -> bool {
+        self.sscgr.is_spread_spectrum_modulation_enabled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spread_spectrum_reference_example() {
+        // 1 MHz PLL input, 1 kHz modulation, 2 % peak, N = 240
+        let settings = SpreadSpectrum::new(Hertz(1_000), 200, SpreadSelect::CenterSpread)
+            .solve(1_000_000, 240)
+            .unwrap();
+
+        assert_eq!(settings.modper, 250);
+        assert_eq!(settings.incstep, 126);
+        assert_eq!(settings.spread, SpreadSelect::CenterSpread);
+    }
+
+    #[test]
+    fn test_spread_spectrum_limits() {
+        let ss = |fmod, deviation| {
+            SpreadSpectrum::new(Hertz(fmod), deviation, SpreadSelect::DownSpread)
+        };
+
+        assert_eq!(ss(0, 100).solve(1_000_000, 336), Err(Error::SpreadSpectrumOutOfRange));
+        assert_eq!(ss(20_000, 100).solve(1_000_000, 336), Err(Error::SpreadSpectrumOutOfRange));
+        assert_eq!(ss(1_000, 0).solve(1_000_000, 336), Err(Error::SpreadSpectrumOutOfRange));
+        assert_eq!(ss(1_000, 300).solve(1_000_000, 336), Err(Error::SpreadSpectrumOutOfRange));
+
+        // MODPER * INCSTEP = 250 * 176 does not fit in 15 bits
+        assert_eq!(ss(1_000, 200).solve(1_000_000, 336), Err(Error::SpreadSpectrumOutOfRange));
+
+        let settings = ss(10_000, 50).solve(2_000_000, 180).unwrap();
+        assert_eq!(settings.modper, 50);
+        assert_eq!(settings.incstep, 118);
+    }
+}
@@ -0,0 +1,141 @@
+//! Timer kernel clocks.
+//!
+//! Timers do not run at PCLKx: with an APB prescaler of 1 they get PCLKx,
+//! otherwise twice PCLKx. Setting TIMPRE raises that to HCLK for prescalers up
+//! to 4 and to four times PCLKx above.
+
+use super::{ apb_divider, TimerPrescaler, RCC };
+
+/// Peripheral bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bus {
+    APB1,
+    APB2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timer {
+    TIM1,
+    TIM2,
+    TIM3,
+    TIM4,
+    TIM5,
+    TIM6,
+    TIM7,
+    TIM8,
+    TIM9,
+    TIM10,
+    TIM11,
+    TIM12,
+    TIM13,
+    TIM14,
+}
+
+impl Timer {
+    /// Bus the timer sits on, and whose prescaler sets its kernel clock
+    pub fn bus(self) -> Bus {
+        match self {
+            Timer::TIM1 | Timer::TIM8 | Timer::TIM9 | Timer::TIM10 | Timer::TIM11 => Bus::APB2,
+            _ => Bus::APB1,
+        }
+    }
+}
+
+/// Kernel clock of the timers on a bus running at `hclk / apb_div`
+pub fn timer_clock_freq(hclk: u32, apb_div: u32, timpre: TimerPrescaler) -> u32 {
+    match timpre {
+        TimerPrescaler::PRE0 if apb_div == 1 => hclk,
+        TimerPrescaler::PRE0 => (hclk / apb_div) * 2,
+        TimerPrescaler::PRE1 if apb_div <= 4 => hclk,
+        TimerPrescaler::PRE1 => (hclk / apb_div) * 4,
+    }
+}
+
+impl RCC {
+    #[inline]
+    pub fn get_timer_prescaler(&self) -> TimerPrescaler {
+        self.dckcfgr.timers_get_clock_prescaler()
+    }
+
+    /// Selects between two and four times PCLKx for the timer clocks
+    #[inline]
+    pub fn set_timer_prescaler(&mut self, timpre: TimerPrescaler) {
+        self.dckcfgr.timers_set_clock_prescaler(timpre);
+    }
+
+    /// Kernel clock of the timers on APB1
+    pub fn timclk1_freq(&self) -> u32 {
+        timer_clock_freq(
+            self.hclk_freq(),
+            apb_divider(self.cfgr.apb1_get_prescaler()),
+            self.get_timer_prescaler()
+        )
+    }
+
+    /// Kernel clock of the timers on APB2
+    pub fn timclk2_freq(&self) -> u32 {
+        timer_clock_freq(
+            self.hclk_freq(),
+            apb_divider(self.cfgr.apb2_get_prescaler()),
+            self.get_timer_prescaler()
+        )
+    }
+
+    #[inline]
+    pub fn timer_clk_freq(&self, timer: Timer) -> u32 {
+        match timer.bus() {
+            Bus::APB1 => self.timclk1_freq(),
+            Bus::APB2 => self.timclk2_freq(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timer_clock_all_prescalers() {
+        const HCLK: u32 = 180_000_000;
+
+        // (APB divider, TIMPRE = 0, TIMPRE = 1)
+        let expected = [
+            (1, HCLK, HCLK),
+            (2, HCLK, HCLK),
+            (4, HCLK / 2, HCLK),
+            (8, HCLK / 4, HCLK / 2),
+            (16, HCLK / 8, HCLK / 4),
+        ];
+
+        for (div, pre0, pre1) in expected {
+            assert_eq!(timer_clock_freq(HCLK, div, TimerPrescaler::PRE0), pre0, "APB / {}", div);
+            assert_eq!(timer_clock_freq(HCLK, div, TimerPrescaler::PRE1), pre1, "APB / {}", div);
+        }
+    }
+
+    #[test]
+    fn test_timer_bus() {
+        assert_eq!(Timer::TIM1.bus(), Bus::APB2);
+        assert_eq!(Timer::TIM8.bus(), Bus::APB2);
+        assert_eq!(Timer::TIM11.bus(), Bus::APB2);
+        assert_eq!(Timer::TIM2.bus(), Bus::APB1);
+        assert_eq!(Timer::TIM7.bus(), Bus::APB1);
+        assert_eq!(Timer::TIM14.bus(), Bus::APB1);
+    }
+
+    #[test]
+    fn test_timclk_from_registers() {
+        let mut rcc = RCC::default();
+
+        // HSI, APB1 / 4, APB2 / 1
+        rcc.cfgr.set(0b101 << 10);
+
+        assert_eq!(rcc.timclk1_freq(), 8_000_000);
+        assert_eq!(rcc.timclk2_freq(), 16_000_000);
+        assert_eq!(rcc.timer_clk_freq(Timer::TIM3), 8_000_000);
+
+        rcc.set_timer_prescaler(TimerPrescaler::PRE1);
+        assert_eq!(rcc.timclk1_freq(), 16_000_000);
+        assert_eq!(rcc.timer_clk_freq(Timer::TIM1), 16_000_000);
+    }
+}
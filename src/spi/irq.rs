@@ -1,5 +1,7 @@
 use core::ptr::{ self, addr_of_mut };
 
+use crate::cortex_m::interrupt;
+
 use super::*;
 
 pub(super) static mut SPI1_STATE: State = State::new();
@@ -33,7 +35,7 @@ impl State {
     }
 }
 
-pub(super) unsafe fn state_mut(spi: &SPI) -> *mut State {
+unsafe fn state_ptr(spi: &SPI) -> *mut State {
     let ptr = spi as *const SPI;
 
     match ptr as usize {
@@ -47,6 +49,11 @@ pub(super) unsafe fn state_mut(spi: &SPI) -> *mut State {
     }
 }
 
+/// Gives `f` exclusive access to the driver state, masking interrupts meanwhile
+pub(super) fn state_mut<R>(spi: &SPI, f: impl FnOnce(&mut State) -> R) -> R {
+    interrupt::free(|_| f(unsafe { &mut *state_ptr(spi) }))
+}
+
 unsafe fn spi_irq_handler(spi: &mut SPI, state: *mut State) {
     let state = &mut *state;
 
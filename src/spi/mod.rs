@@ -4,7 +4,7 @@ use core::{ fmt, mem, slice };
 
 use irq::{ state_mut, Status };
 
-use crate::{ peripheral, rcc::rcc, PeripheralClock };
+use crate::{ interrupt::Interrupt, peripheral, rcc::rcc, PeripheralClock };
 
 use self::register::*;
 
@@ -153,8 +153,7 @@ impl SPI {
     }
 
     pub fn write_data_begin(&mut self, data: &[u8]) -> Result<()> {
-        unsafe {
-            let state = &mut *state_mut(&self);
+        state_mut(self, |state| -> Result<()> {
             match state.status {
                 Status::Ready => Ok(()),
                 Status::BusyRx => Err(Error::BusyError("RX in progress")),
@@ -164,7 +163,9 @@ impl SPI {
 
             state.tx_buf = (data.as_ptr(), data.len());
             state.status = Status::BusyTx;
-        }
+
+            Ok(())
+        })?;
 
         self.cr2.enable_tx_empty_interrupt();
 
@@ -202,8 +203,7 @@ impl SPI {
     }
 
     pub fn read_data_begin(&mut self, data: &mut [u8]) -> Result<()> {
-        unsafe {
-            let state = &mut *state_mut(&self);
+        state_mut(self, |state| -> Result<()> {
             match state.status {
                 Status::Ready => Ok(()),
                 Status::BusyRx => Err(Error::BusyError("RX in progress")),
@@ -213,12 +213,29 @@ impl SPI {
 
             state.rx_buf = (data.as_mut_ptr(), data.len());
             state.status = Status::BusyRx;
-        }
+
+            Ok(())
+        })?;
 
         self.cr2.enable_rx_not_empty_interrupt();
 
         Ok(())
     }
+
+    /// Global interrupt raised by this peripheral
+    pub fn interrupt(&self) -> Interrupt {
+        let ptr = self as *const Self;
+
+        match ptr as usize {
+            0x4001_3000 => Interrupt::SPI1,
+            0x4000_3800 => Interrupt::SPI2,
+            0x4000_3c00 => Interrupt::SPI3,
+            0x4001_3400 => Interrupt::SPI4,
+            0x4001_5000 => Interrupt::SPI5,
+            0x4001_5400 => Interrupt::SPI6,
+            _ => panic!(),
+        }
+    }
 }
 
 impl PeripheralClock for SPI {
@@ -0,0 +1,71 @@
+//! Frequency units.
+
+use core::{ fmt, ops::Div };
+
+/// Frequency in Hz
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hertz(pub u32);
+
+#[allow(non_snake_case)]
+impl Hertz {
+    #[inline]
+    pub const fn raw(self) -> u32 {
+        self.0
+    }
+
+    #[inline]
+    pub const fn to_kHz(self) -> u32 {
+        self.0 / 1_000
+    }
+
+    #[inline]
+    pub const fn to_MHz(self) -> u32 {
+        self.0 / 1_000_000
+    }
+}
+
+impl Div<u32> for Hertz {
+    type Output = Hertz;
+
+    #[inline]
+    fn div(self, rhs: u32) -> Self::Output {
+        Hertz(self.0 / rhs)
+    }
+}
+
+impl fmt::Display for Hertz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 % 1_000_000 == 0 {
+            f.write_fmt(format_args!("{} MHz", self.0 / 1_000_000))
+        } else if self.0 % 1_000 == 0 {
+            f.write_fmt(format_args!("{} kHz", self.0 / 1_000))
+        } else {
+            f.write_fmt(format_args!("{} Hz", self.0))
+        }
+    }
+}
+
+/// Creates a [`Hertz`] from a plain integer, e.g. `8.MHz()`
+#[allow(non_snake_case)]
+pub trait U32Ext {
+    fn Hz(self) -> Hertz;
+    fn kHz(self) -> Hertz;
+    fn MHz(self) -> Hertz;
+}
+
+impl U32Ext for u32 {
+    #[inline]
+    fn Hz(self) -> Hertz {
+        Hertz(self)
+    }
+
+    #[inline]
+    fn kHz(self) -> Hertz {
+        Hertz(self * 1_000)
+    }
+
+    #[inline]
+    fn MHz(self) -> Hertz {
+        Hertz(self * 1_000_000)
+    }
+}
@@ -1,5 +1,7 @@
 use core::ptr::{ self, addr_of_mut };
 
+use crate::cortex_m::interrupt;
+
 use super::*;
 
 pub(super) static mut USART1_STATE: State = State::new();
@@ -35,7 +37,7 @@ impl State {
     }
 }
 
-pub(super) unsafe fn state_mut(usart: &USART) -> *mut State {
+unsafe fn state_ptr(usart: &USART) -> *mut State {
     let ptr = usart as *const USART;
 
     match ptr as usize {
@@ -51,6 +53,11 @@ pub(super) unsafe fn state_mut(usart: &USART) -> *mut State {
     }
 }
 
+/// Gives `f` exclusive access to the driver state, masking interrupts meanwhile
+pub(super) fn state_mut<R>(usart: &USART, f: impl FnOnce(&mut State) -> R) -> R {
+    interrupt::free(|_| f(unsafe { &mut *state_ptr(usart) }))
+}
+
 unsafe fn usart_irq_handler(usart: &mut USART, state: *mut State) {
     let state = &mut *state;
 
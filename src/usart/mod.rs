@@ -2,7 +2,7 @@ use core::{ fmt, mem::size_of, ptr, slice };
 
 use irq::{ state_mut, Status };
 
-use crate::{ peripheral, rcc::rcc, PeripheralClock };
+use crate::{ interrupt::Interrupt, peripheral, rcc::rcc, PeripheralClock };
 
 use self::register::*;
 
@@ -181,8 +181,7 @@ impl USART {
     }
 
     pub fn write_data_begin(&mut self, data: &[u8]) -> Result<()> {
-        unsafe {
-            let state = &mut *state_mut(&self);
+        state_mut(self, |state| -> Result<()> {
             (match state.status {
                 Status::Ready => Ok(()),
                 Status::BusyRx => Err(Error::BusyError("RX in progress")),
@@ -192,7 +191,9 @@ impl USART {
 
             state.tx_buf = (data.as_ptr(), data.len());
             state.status = Status::BusyTx;
-        }
+
+            Ok(())
+        })?;
 
         self.cr1.enable_tx_empty_interrupt();
 
@@ -236,8 +237,7 @@ impl USART {
     }
 
     pub fn read_data_begin(&mut self, data: &mut [u8]) -> Result<()> {
-        unsafe {
-            let state = &mut *state_mut(&self);
+        state_mut(self, |state| -> Result<()> {
             (match state.status {
                 Status::Ready => Ok(()),
                 Status::BusyRx => Err(Error::BusyError("RX in progress")),
@@ -247,13 +247,32 @@ impl USART {
 
             state.rx_buf = (data.as_mut_ptr(), data.len());
             state.status = Status::BusyRx;
-        }
+
+            Ok(())
+        })?;
 
         self.cr1.enable_rx_not_empty_interrupt();
 
         Ok(())
     }
 
+    /// Global interrupt raised by this peripheral
+    pub fn interrupt(&self) -> Interrupt {
+        let ptr = ptr::from_ref(self);
+
+        match ptr as usize {
+            0x4001_1000 => Interrupt::USART1,
+            0x4000_4400 => Interrupt::USART2,
+            0x4000_4800 => Interrupt::USART3,
+            0x4000_4c00 => Interrupt::UART4,
+            0x4000_5000 => Interrupt::UART5,
+            0x4001_1400 => Interrupt::USART6,
+            0x4000_7800 => Interrupt::UART7,
+            0x4000_7c00 => Interrupt::UART8,
+            _ => panic!(),
+        }
+    }
+
     fn pclk_freq(&self) -> u32 {
         let ptr = ptr::from_ref(self);
 